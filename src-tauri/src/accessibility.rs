@@ -0,0 +1,165 @@
+use serde::{Deserialize, Serialize};
+
+/// Most elements read from one window; large web pages are cut off here
+pub const MAX_NODES: usize = 2000;
+
+/// Deepest nesting walked
+pub const MAX_DEPTH: usize = 50;
+
+/// Fewer words than this and the tree is treated as unreadable (canvas apps,
+/// Electron without accessibility enabled) — OCR is used instead
+pub const MIN_WORDS: usize = 12;
+
+/// Element roles, reduced from each platform's own role names
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Role {
+    /// Document or web area; its text usually lives in its children
+    Document,
+    Heading,
+    Paragraph,
+    /// Static text and labels
+    Text,
+    /// Text field or text area: what the user is writing
+    Editable,
+    ListItem,
+    Cell,
+    Link,
+    /// Toolbars, menus, buttons, tabs, scroll bars — skipped with their subtree
+    Chrome,
+    /// Groups, panes, lists and anything else: no text of its own
+    Container,
+}
+
+impl Role {
+    fn has_text(self) -> bool {
+        !matches!(self, Role::Chrome | Role::Container)
+    }
+}
+
+/// One element of the accessibility tree, in document order
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Node {
+    pub depth: usize,
+    pub role: Role,
+    /// Title, label or description
+    pub name: String,
+    /// Text content or field value
+    pub value: String,
+    pub focused: bool,
+}
+
+/// The element with keyboard focus
+#[derive(Debug, Clone, Serialize)]
+pub struct FocusedElement {
+    pub role: Role,
+    pub name: String,
+}
+
+/// Text read from the focused window's accessibility tree
+#[derive(Debug, Clone, Serialize)]
+pub struct WindowText {
+    /// Content text, one element per line, without UI chrome
+    pub text: String,
+    pub focused: Option<FocusedElement>,
+}
+
+impl WindowText {
+    /// Enough text to stand in for OCR
+    pub fn is_usable(&self) -> bool {
+        self.text.split_whitespace().count() >= MIN_WORDS
+    }
+}
+
+/// Assemble window text from a flattened tree. Chrome subtrees are skipped;
+/// an element that has text covers its subtree (a paragraph's links are part
+/// of the paragraph), except documents, whose own text is only used when they
+/// have no children.
+pub fn collect(nodes: &[Node]) -> WindowText {
+    let mut lines: Vec<String> = Vec::new();
+    let mut focused = None;
+    // Depth of the subtree being skipped
+    let mut skip_below: Option<usize> = None;
+
+    for (i, node) in nodes.iter().enumerate() {
+        if node.focused && focused.is_none() {
+            focused = Some(FocusedElement { role: node.role, name: node.name.clone() });
+        }
+        match skip_below {
+            Some(depth) if node.depth > depth => continue,
+            _ => skip_below = None,
+        }
+        if node.role == Role::Chrome {
+            skip_below = Some(node.depth);
+            continue;
+        }
+        if !node.role.has_text() {
+            continue;
+        }
+        let has_children = nodes.get(i + 1).is_some_and(|next| next.depth > node.depth);
+        if node.role == Role::Document && has_children {
+            continue;
+        }
+
+        // Object replacement characters stand in for embedded children (AT-SPI)
+        let raw = if node.value.trim().is_empty() { &node.name } else { &node.value };
+        let text = raw.replace('\u{fffc}', " ");
+        let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+        if text.is_empty() {
+            continue;
+        }
+        skip_below = Some(node.depth);
+        if lines.last() != Some(&text) {
+            lines.push(text);
+        }
+    }
+
+    WindowText { text: lines.join("\n"), focused }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn node(depth: usize, role: Role, name: &str, value: &str) -> Node {
+        Node { depth, role, name: name.to_string(), value: value.to_string(), focused: false }
+    }
+
+    #[test]
+    fn skips_chrome_and_keeps_document_text() {
+        let mut editor = node(2, Role::Editable, "Body", "Dear team, the launch moves to Thursday.");
+        editor.focused = true;
+        let nodes = vec![
+            node(0, Role::Container, "Mail", ""),
+            node(1, Role::Chrome, "Toolbar", ""),
+            node(2, Role::Text, "Send", ""),
+            node(2, Role::Text, "Attach", ""),
+            node(1, Role::Document, "Message", ""),
+            node(2, Role::Heading, "Launch update", ""),
+            editor,
+        ];
+        let text = collect(&nodes);
+        assert_eq!(text.text, "Launch update\nDear team, the launch moves to Thursday.");
+        let focused = text.focused.expect("focused element");
+        assert_eq!((focused.role, focused.name.as_str()), (Role::Editable, "Body"));
+    }
+
+    #[test]
+    fn text_covers_its_subtree() {
+        let nodes = vec![
+            node(0, Role::Document, "Page", "whole page text"),
+            node(1, Role::Paragraph, "", "Read the \u{fffc} before Friday."),
+            node(2, Role::Link, "rollout plan", ""),
+            node(1, Role::Paragraph, "", "Questions go to the channel."),
+        ];
+        let text = collect(&nodes);
+        assert_eq!(text.text, "Read the before Friday.\nQuestions go to the channel.");
+    }
+
+    #[test]
+    fn childless_document_uses_its_own_text() {
+        let nodes = vec![node(0, Role::Container, "", ""), node(1, Role::Document, "", "Plain text file")];
+        assert_eq!(collect(&nodes).text, "Plain text file");
+        assert!(!collect(&nodes).is_usable());
+    }
+}
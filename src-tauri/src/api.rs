@@ -1,5 +1,13 @@
+use base64::Engine;
 use serde_json::json;
 
+/// A file uploaded alongside a capture (e.g. the original clipboard image)
+pub struct Attachment {
+    pub filename: String,
+    pub mime_type: String,
+    pub data: Vec<u8>,
+}
+
 /// Capture text as a new memory via the Reattend API
 pub async fn capture(
     api_url: &str,
@@ -7,6 +15,7 @@ pub async fn capture(
     text: &str,
     source: &str,
     metadata: Option<serde_json::Value>,
+    attachments: &[Attachment],
 ) -> Result<String, String> {
     let mut body = json!({
         "text": text,
@@ -15,6 +24,16 @@ pub async fn capture(
     if let Some(meta) = metadata {
         body["metadata"] = meta;
     }
+    if !attachments.is_empty() {
+        body["attachments"] = attachments
+            .iter()
+            .map(|a| json!({
+                "filename": a.filename,
+                "mime_type": a.mime_type,
+                "data": base64::engine::general_purpose::STANDARD.encode(&a.data),
+            }))
+            .collect();
+    }
 
     let client = reqwest::Client::new();
     let resp = client
@@ -35,6 +54,25 @@ pub async fn capture(
     Ok(body["id"].as_str().unwrap_or("").to_string())
 }
 
+/// Delete a previously captured memory (undo / retract)
+pub async fn delete_capture(api_url: &str, token: &str, id: &str) -> Result<(), String> {
+    let client = reqwest::Client::new();
+    let resp = client
+        .delete(format!("{}/api/tray/capture/{}", api_url, urlencoding::encode(id)))
+        .header("Authorization", format!("Bearer {}", token))
+        .send()
+        .await
+        .map_err(|e| format!("Network error: {}", e))?;
+
+    if !resp.status().is_success() {
+        let status = resp.status();
+        let body = resp.text().await.unwrap_or_default();
+        return Err(format!("API error {}: {}", status, body));
+    }
+
+    Ok(())
+}
+
 /// Search memories via the Reattend API
 pub async fn search(api_url: &str, token: &str, query: &str) -> Result<serde_json::Value, String> {
     let client = reqwest::Client::new();
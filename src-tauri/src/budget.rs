@@ -0,0 +1,294 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use tauri_plugin_store::StoreExt;
+
+/// Store file holding the persistent hourly/daily counters
+const COUNTER_STORE: &str = "capture_budgets.json";
+
+/// Passive capture sources that are subject to a budget.
+/// Manual selections are never budgeted — the user asked for them explicitly.
+pub const BUDGETED_SOURCES: &[&str] = &["screen", "clipboard", "writing"];
+
+/// Serializes check-and-count of the counters (captures are sent from spawned tasks)
+static COUNTER_LOCK: std::sync::Mutex<()> = std::sync::Mutex::new(());
+
+/// Upload limits for one source. A limit of 0 means unlimited.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy)]
+pub struct BudgetLimit {
+    pub per_hour: u32,
+    pub per_day: u32,
+}
+
+/// Persistent counter for one source. Windows are UTC hour/day indices since the epoch.
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+struct BudgetCounter {
+    hour_window: i64,
+    hour_count: u32,
+    day_window: i64,
+    day_count: u32,
+}
+
+impl BudgetCounter {
+    /// Reset counts whose window has rolled over
+    fn roll(&mut self, now: i64) {
+        let hour = now / 3600;
+        let day = now / 86400;
+        if self.hour_window != hour {
+            self.hour_window = hour;
+            self.hour_count = 0;
+        }
+        if self.day_window != day {
+            self.day_window = day;
+            self.day_count = 0;
+        }
+    }
+
+    fn is_over(&self, limit: &BudgetLimit) -> bool {
+        (limit.per_hour > 0 && self.hour_count >= limit.per_hour)
+            || (limit.per_day > 0 && self.day_count >= limit.per_day)
+    }
+
+    /// Count one upload at `now` if the limit allows it, or regardless when
+    /// `over_budget` is set. Returns whether it was counted.
+    fn take(&mut self, limit: &BudgetLimit, now: i64, over_budget: bool) -> bool {
+        self.roll(now);
+        if self.is_over(limit) && !over_budget {
+            return false;
+        }
+        self.hour_count += 1;
+        self.day_count += 1;
+        true
+    }
+
+    /// Undo a `take` made at `taken_at`; uploads from a window that has since
+    /// rolled over are no longer counted anyway
+    fn give_back(&mut self, taken_at: i64, now: i64) {
+        self.roll(now);
+        if self.hour_window == taken_at / 3600 {
+            self.hour_count = self.hour_count.saturating_sub(1);
+        }
+        if self.day_window == taken_at / 86400 {
+            self.day_count = self.day_count.saturating_sub(1);
+        }
+    }
+}
+
+/// Budget state for one source, as returned to the frontend
+#[derive(Debug, Serialize, Clone)]
+pub struct BudgetStatus {
+    pub source: String,
+    pub limit: BudgetLimit,
+    pub hour_count: u32,
+    pub day_count: u32,
+    pub exhausted: bool,
+}
+
+fn now_secs() -> i64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs() as i64
+}
+
+/// Default limits — generous enough for a normal workday, low enough to stop runaway uploads
+fn default_limit(source: &str) -> BudgetLimit {
+    match source {
+        "screen" => BudgetLimit { per_hour: 120, per_day: 1000 },
+        "clipboard" => BudgetLimit { per_hour: 60, per_day: 400 },
+        "writing" => BudgetLimit { per_hour: 60, per_day: 400 },
+        _ => BudgetLimit { per_hour: 0, per_day: 0 },
+    }
+}
+
+/// Load the configured limits (config.json → "capture_budgets"), falling back to defaults
+pub fn load_limits(app: &tauri::AppHandle) -> HashMap<String, BudgetLimit> {
+    let configured: HashMap<String, BudgetLimit> = app
+        .store("config.json")
+        .ok()
+        .and_then(|s| s.get("capture_budgets"))
+        .and_then(|v| serde_json::from_value(v).ok())
+        .unwrap_or_default();
+
+    BUDGETED_SOURCES
+        .iter()
+        .map(|source| {
+            let limit = configured.get(*source).copied().unwrap_or_else(|| default_limit(source));
+            (source.to_string(), limit)
+        })
+        .collect()
+}
+
+/// Persist new limits. Unknown sources are ignored.
+pub fn save_limits(app: &tauri::AppHandle, limits: HashMap<String, BudgetLimit>) -> Result<(), String> {
+    let limits: HashMap<String, BudgetLimit> = limits
+        .into_iter()
+        .filter(|(source, _)| BUDGETED_SOURCES.contains(&source.as_str()))
+        .collect();
+    let store = app.store("config.json").map_err(|e| e.to_string())?;
+    store.set("capture_budgets", serde_json::json!(limits));
+    store.save().map_err(|e| e.to_string())
+}
+
+fn load_counter(app: &tauri::AppHandle, source: &str) -> BudgetCounter {
+    let mut counter: BudgetCounter = app
+        .store(COUNTER_STORE)
+        .ok()
+        .and_then(|s| s.get(source))
+        .and_then(|v| serde_json::from_value(v).ok())
+        .unwrap_or_default();
+    counter.roll(now_secs());
+    counter
+}
+
+fn save_counter(app: &tauri::AppHandle, source: &str, counter: &BudgetCounter) {
+    if let Ok(store) = app.store(COUNTER_STORE) {
+        store.set(source, serde_json::json!(counter));
+        let _ = store.save();
+    }
+}
+
+fn limit_for(app: &tauri::AppHandle, source: &str) -> BudgetLimit {
+    load_limits(app)
+        .get(source)
+        .copied()
+        .unwrap_or_else(|| default_limit(source))
+}
+
+/// Check whether a source has used up its hourly or daily budget
+pub fn is_exhausted(app: &tauri::AppHandle, source: &str) -> bool {
+    if !BUDGETED_SOURCES.contains(&source) {
+        return false;
+    }
+    load_counter(app, source).is_over(&limit_for(app, source))
+}
+
+/// Whether a capture may go past a spent budget: the screen read right after
+/// an app switch, which is all a source captures once its budget is spent
+pub fn may_exceed(metadata: Option<&serde_json::Value>) -> bool {
+    metadata.is_some_and(|m| m["trigger"] == "app_switch")
+}
+
+/// Count an upload against a source's budget before it is sent, in one step
+/// with the check so concurrent captures can't overshoot the limit. With
+/// `over_budget` the upload is counted even past the limit. Returns the
+/// reservation to `release` if the upload fails, or None when the budget is
+/// exhausted. Unbudgeted sources always get a reservation.
+pub fn reserve(app: &tauri::AppHandle, source: &str, over_budget: bool) -> Option<i64> {
+    let now = now_secs();
+    if !BUDGETED_SOURCES.contains(&source) {
+        return Some(now);
+    }
+    let _guard = COUNTER_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let mut counter = load_counter(app, source);
+    if !counter.take(&limit_for(app, source), now, over_budget) {
+        return None;
+    }
+    save_counter(app, source, &counter);
+    Some(now)
+}
+
+/// Return a reservation whose upload failed
+pub fn release(app: &tauri::AppHandle, source: &str, reservation: i64) {
+    if !BUDGETED_SOURCES.contains(&source) {
+        return;
+    }
+    let _guard = COUNTER_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let mut counter = load_counter(app, source);
+    counter.give_back(reservation, now_secs());
+    save_counter(app, source, &counter);
+}
+
+/// Current limits and counters for every budgeted source
+pub fn status(app: &tauri::AppHandle) -> Vec<BudgetStatus> {
+    let limits = load_limits(app);
+    BUDGETED_SOURCES
+        .iter()
+        .map(|source| {
+            let limit = limits.get(*source).copied().unwrap_or_else(|| default_limit(source));
+            let counter = load_counter(app, source);
+            BudgetStatus {
+                source: source.to_string(),
+                limit,
+                hour_count: counter.hour_count,
+                day_count: counter.day_count,
+                exhausted: counter.is_over(&limit),
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LIMIT: BudgetLimit = BudgetLimit { per_hour: 2, per_day: 3 };
+    /// 2024-01-15 10:00:00 UTC
+    const MORNING: i64 = 1_705_312_800;
+
+    #[test]
+    fn stops_at_the_hourly_and_daily_limits() {
+        let mut counter = BudgetCounter::default();
+        assert!(counter.take(&LIMIT, MORNING, false));
+        assert!(counter.take(&LIMIT, MORNING + 60, false));
+        assert!(!counter.take(&LIMIT, MORNING + 120, false));
+        assert!(counter.is_over(&LIMIT));
+
+        // A new hour frees the hourly budget, until the daily one runs out
+        assert!(counter.take(&LIMIT, MORNING + 3600, false));
+        assert!(!counter.take(&LIMIT, MORNING + 7200, false));
+        assert_eq!((counter.hour_count, counter.day_count), (0, 3));
+
+        // Forced uploads are still counted
+        assert!(counter.take(&LIMIT, MORNING + 7200, true));
+        assert_eq!(counter.day_count, 4);
+
+        let mut unlimited = BudgetCounter::default();
+        let none = BudgetLimit { per_hour: 0, per_day: 0 };
+        assert!((0..1000).all(|i| unlimited.take(&none, MORNING + i, false)));
+    }
+
+    #[test]
+    fn app_switch_captures_get_through_a_spent_hourly_budget() {
+        let limit = default_limit("screen");
+        let mut counter = BudgetCounter::default();
+        for i in 0..limit.per_hour {
+            assert!(counter.take(&limit, MORNING + i as i64, false));
+        }
+        assert!(counter.is_over(&limit));
+
+        let periodic = serde_json::json!({ "capture_type": "screen" });
+        let switch = serde_json::json!({ "capture_type": "screen", "trigger": "app_switch" });
+        assert!(!counter.take(&limit, MORNING + 600, may_exceed(Some(&periodic))));
+        assert!(!may_exceed(None));
+        assert!(counter.take(&limit, MORNING + 600, may_exceed(Some(&switch))));
+        assert_eq!(counter.hour_count, limit.per_hour + 1);
+    }
+
+    #[test]
+    fn counts_reset_when_the_day_rolls_over() {
+        let mut counter = BudgetCounter::default();
+        let before_midnight = MORNING + 13 * 3600 + 59 * 60;
+        assert!(counter.take(&LIMIT, before_midnight, false));
+        assert!(counter.take(&LIMIT, before_midnight + 30, false));
+        assert!(!counter.take(&LIMIT, before_midnight + 50, false));
+
+        let after_midnight = before_midnight + 120;
+        assert!(counter.take(&LIMIT, after_midnight, false));
+        assert_eq!((counter.hour_count, counter.day_count), (1, 1));
+        assert_eq!(counter.day_window, after_midnight / 86400);
+    }
+
+    #[test]
+    fn released_uploads_only_refund_their_own_window() {
+        let mut counter = BudgetCounter::default();
+        assert!(counter.take(&LIMIT, MORNING, false));
+        assert!(counter.take(&LIMIT, MORNING, false));
+        counter.give_back(MORNING, MORNING + 5);
+        assert_eq!((counter.hour_count, counter.day_count), (1, 1));
+        assert!(counter.take(&LIMIT, MORNING + 10, false));
+
+        // Taken in the previous hour: only the daily count still holds it
+        counter.give_back(MORNING, MORNING + 3600);
+        assert_eq!((counter.hour_count, counter.day_count), (0, 1));
+    }
+}
@@ -0,0 +1,179 @@
+use crate::{api, budget, dedup, history, pii, rules, secrets};
+use crate::history::{CaptureOutcome, CaptureRecord};
+use std::collections::BTreeMap;
+
+/// Text cleared to leave the machine
+pub struct Scrubbed {
+    pub text: String,
+    /// Secret rules that matched (and were redacted), in text order
+    pub secrets: Vec<&'static str>,
+    /// Redactions per PII entity type
+    pub pii: BTreeMap<String, usize>,
+}
+
+impl Scrubbed {
+    /// Whether anything was removed from the text
+    pub fn altered(&self) -> bool {
+        !self.secrets.is_empty() || !self.pii.is_empty()
+    }
+}
+
+/// Filter for any text about to be uploaded: the secret scan, then PII
+/// redaction, with the configured actions. Err((reason, redacted text)) when
+/// a `Drop` rule matched and nothing may be sent.
+pub fn scrub(app: &tauri::AppHandle, text: &str) -> Result<Scrubbed, (String, String)> {
+    let scan = secrets::sanitize(text, &secrets::load_actions(app));
+    if let Some(rule) = scan.dropped_by {
+        return Err((format!("Dropped: contains {}", rule.replace('_', " ")), scan.text));
+    }
+    // PII redaction: mask or hash configured entity types
+    let (text, pii) = pii::redact(&scan.text, &pii::load_modes(app), &pii::install_salt(app));
+    Ok(Scrubbed { text, secrets: scan.hits, pii })
+}
+
+/// Ask the server for memories related to on-screen text, after the same
+/// secret scan and PII redaction captures get
+pub async fn analyze(
+    app: &tauri::AppHandle,
+    api_url: &str,
+    token: &str,
+    text: &str,
+    app_name: &str,
+) -> Result<serde_json::Value, String> {
+    let scrubbed = scrub(app, text).map_err(|(reason, _)| reason)?;
+    api::analyze(api_url, token, &scrubbed.text, app_name).await
+}
+
+/// Send a capture to the Reattend API.
+/// Every capture path goes through here so title/domain rules are applied,
+/// secrets and PII are scrubbed, near-duplicates and budgets are enforced and each attempt lands in the local capture history.
+pub async fn submit(
+    app: &tauri::AppHandle,
+    api_url: &str,
+    token: &str,
+    text: &str,
+    source: &str,
+    metadata: Option<serde_json::Value>,
+) -> Result<String, String> {
+    submit_with_attachments(app, api_url, token, text, source, metadata, Vec::new()).await
+}
+
+/// Like `submit`, with files uploaded alongside the text.
+/// Attachments are withheld when the text had secrets or PII removed — the
+/// original file would still contain them.
+pub async fn submit_with_attachments(
+    app: &tauri::AppHandle,
+    api_url: &str,
+    token: &str,
+    text: &str,
+    source: &str,
+    metadata: Option<serde_json::Value>,
+    attachments: Vec<api::Attachment>,
+) -> Result<String, String> {
+    deliver(app, api_url, token, text, source, metadata, attachments).await.1
+}
+
+/// Like `submit_with_attachments`, also returning what became of the
+/// capture, so a refused capture can be told apart from a failed upload.
+pub async fn deliver(
+    app: &tauri::AppHandle,
+    api_url: &str,
+    token: &str,
+    text: &str,
+    source: &str,
+    mut metadata: Option<serde_json::Value>,
+    mut attachments: Vec<api::Attachment>,
+) -> (CaptureOutcome, Result<String, String>) {
+    // Title/domain rules: a blocked window's text is not even kept in the history
+    if let Err(reason) = rules::check(&rules::load(app), metadata.as_ref()) {
+        let mut entry = CaptureRecord::new(source, metadata.as_ref(), "", CaptureOutcome::Blocked);
+        entry.error = Some(reason.clone());
+        history::record(app, entry);
+        return (CaptureOutcome::Blocked, Err(reason));
+    }
+
+    // Secrets and PII are scrubbed next: nothing below may see the raw text
+    let scrubbed = match scrub(app, text) {
+        Ok(scrubbed) => scrubbed,
+        Err((reason, scanned)) => {
+            let mut entry = CaptureRecord::new(source, metadata.as_ref(), &scanned, CaptureOutcome::Blocked);
+            entry.error = Some(reason.clone());
+            history::record(app, entry);
+            return (CaptureOutcome::Blocked, Err(reason));
+        }
+    };
+    let text = scrubbed.text.as_str();
+    if !scrubbed.secrets.is_empty() {
+        let meta = metadata.get_or_insert_with(|| serde_json::json!({}));
+        meta["secrets_redacted"] = serde_json::json!(scrubbed.secrets.len());
+    }
+    if !scrubbed.pii.is_empty() {
+        let meta = metadata.get_or_insert_with(|| serde_json::json!({}));
+        meta["pii_redactions"] = serde_json::json!(scrubbed.pii);
+    }
+    if scrubbed.altered() {
+        let meta = metadata.get_or_insert_with(|| serde_json::json!({}));
+        meta["text_altered"] = serde_json::json!(true);
+        if !attachments.is_empty() {
+            attachments.clear();
+            meta["attachments_withheld"] = serde_json::json!(true);
+        }
+    }
+
+    // Once a source's budget is spent it only captures on app switches
+    let over_budget = budget::may_exceed(metadata.as_ref());
+    let Some(reservation) = budget::reserve(app, source, over_budget) else {
+        let mut entry = CaptureRecord::new(source, metadata.as_ref(), text, CaptureOutcome::Throttled);
+        entry.error = Some("Capture budget exhausted".to_string());
+        history::record(app, entry);
+        return (CaptureOutcome::Throttled, Err("Capture budget exhausted".to_string()));
+    };
+
+    // Near-duplicate of a recent upload from any stream. Manual saves always go
+    // through, but still suppress later passive copies of the same text.
+    let fingerprint = match dedup::check_and_claim(app, text, source) {
+        Ok(fingerprint) => Some(fingerprint),
+        Err(earlier) if dedup::PASSIVE_SOURCES.contains(&source) => {
+            budget::release(app, source, reservation);
+            let reason = format!("Duplicate of a recent {} capture", earlier);
+            let mut entry = CaptureRecord::new(source, metadata.as_ref(), text, CaptureOutcome::Duplicate);
+            entry.error = Some(reason.clone());
+            history::record(app, entry);
+            return (CaptureOutcome::Duplicate, Err(reason));
+        }
+        Err(_) => None,
+    };
+
+    let mut entry = CaptureRecord::new(source, metadata.as_ref(), text, CaptureOutcome::Sent);
+    let result = api::capture(api_url, token, text, source, metadata, &attachments).await;
+    match result {
+        Ok(ref id) => {
+            if !id.is_empty() {
+                entry.server_id = Some(id.clone());
+            }
+        }
+        Err(ref e) => {
+            budget::release(app, source, reservation);
+            if let Some(fingerprint) = fingerprint {
+                dedup::release(app, fingerprint);
+            }
+            entry.outcome = CaptureOutcome::Failed;
+            entry.error = Some(e.clone());
+        }
+    }
+    let outcome = entry.outcome;
+    history::record(app, entry);
+    (outcome, result)
+}
+
+/// Delete a sent capture from the server and mark it retracted in the history
+pub async fn retract(
+    app: &tauri::AppHandle,
+    api_url: &str,
+    token: &str,
+    server_id: &str,
+) -> Result<(), String> {
+    api::delete_capture(api_url, token, server_id).await?;
+    history::mark_retracted(app, server_id);
+    Ok(())
+}
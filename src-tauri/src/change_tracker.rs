@@ -0,0 +1,115 @@
+use crate::similarity::MinHash;
+
+/// Windows remembered at once; the least recently seen one is forgotten first
+pub const MAX_TRACKED: usize = 32;
+
+/// Identify a window by app and document, ignoring unsaved-changes markers in the title
+pub fn window_key(app_name: &str, title: &str) -> String {
+    format!("{}\u{1f}{}", app_name.to_lowercase(), crate::writing::document_key(title))
+}
+
+struct WindowState {
+    /// Cleaned OCR text from the last time the window was seen
+    text: String,
+    /// Signature of the text last captured from the window
+    captured: Option<MinHash>,
+}
+
+/// Screen-change state per app and window, in a bounded LRU. Switching
+/// between windows compares each against its own history, so returning to
+/// an unchanged document is not captured again.
+#[derive(Default)]
+pub struct ChangeTracker {
+    /// Least recently seen first
+    windows: Vec<(String, WindowState)>,
+}
+
+impl ChangeTracker {
+    fn touch(&mut self, key: &str) -> &mut WindowState {
+        let state = match self.windows.iter().position(|(k, _)| k == key) {
+            Some(pos) => self.windows.remove(pos).1,
+            None => WindowState { text: String::new(), captured: None },
+        };
+        self.windows.push((key.to_string(), state));
+        if self.windows.len() > MAX_TRACKED {
+            self.windows.remove(0);
+        }
+        &mut self.windows.last_mut().expect("just pushed").1
+    }
+
+    /// Record the latest text of a window; returns the text seen there before
+    pub fn update_text(&mut self, key: &str, text: &str) -> Option<String> {
+        let previous = std::mem::replace(&mut self.touch(key).text, text.to_string());
+        if previous.is_empty() { None } else { Some(previous) }
+    }
+
+    /// Similarity to the last capture from this window, 0 if never captured
+    pub fn similarity_to_captured(&self, key: &str, signature: &MinHash) -> f64 {
+        self.windows
+            .iter()
+            .find(|(k, _)| k == key)
+            .and_then(|(_, state)| state.captured.as_ref())
+            .map(|captured| captured.similarity(signature))
+            .unwrap_or(0.0)
+    }
+
+    pub fn mark_captured(&mut self, key: &str, signature: MinHash) {
+        self.touch(key).captured = Some(signature);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::similarity::DEFAULT_THRESHOLD;
+
+    const REPORT: &str = "Quarterly report draft: revenue grew in every region, with the \
+        strongest gains in the enterprise segment and steady renewals elsewhere.";
+    const INBOX: &str = "Inbox: Dana asked for the updated hiring plan, the design review \
+        moved to Thursday, and the vendor sent a revised contract for signature.";
+
+    /// What the capture loop does with a screen read; true when it is captured
+    fn capture(tracker: &mut ChangeTracker, key: &str, text: &str) -> bool {
+        tracker.update_text(key, text);
+        let signature = MinHash::of(text);
+        if tracker.similarity_to_captured(key, &signature) > DEFAULT_THRESHOLD {
+            return false;
+        }
+        tracker.mark_captured(key, signature);
+        true
+    }
+
+    #[test]
+    fn returning_to_an_unchanged_window_is_not_captured_again() {
+        let mut tracker = ChangeTracker::default();
+        let report = window_key("Pages", "Report.pages");
+        let inbox = window_key("Mail", "Inbox");
+        assert!(capture(&mut tracker, &report, REPORT));
+        assert!(capture(&mut tracker, &inbox, INBOX));
+        assert!(!capture(&mut tracker, &window_key("Pages", "● Report.pages — Edited"), REPORT));
+        assert!(!capture(&mut tracker, &inbox, INBOX));
+        // New content in the same window is captured
+        let edited = format!("{} Next quarter we expect hiring to slow while we integrate the acquisition.", REPORT);
+        assert!(capture(&mut tracker, &report, &edited.replace("Quarterly report draft", "Final numbers")));
+    }
+
+    #[test]
+    fn least_recently_seen_window_is_evicted() {
+        let mut tracker = ChangeTracker::default();
+        let key = |i: usize| window_key("Notes", &format!("Note {}", i));
+        assert!(capture(&mut tracker, &key(0), REPORT));
+        assert!(capture(&mut tracker, &key(1), INBOX));
+        for i in 2..=MAX_TRACKED {
+            tracker.update_text(&key(i), "other text");
+            // Keep window 1 recently seen
+            if i == MAX_TRACKED / 2 {
+                assert_eq!(tracker.update_text(&key(1), INBOX).as_deref(), Some(INBOX));
+            }
+        }
+        assert_eq!(tracker.windows.len(), MAX_TRACKED);
+        // Window 0 was forgotten, so its unchanged text counts as new again
+        assert_eq!(tracker.similarity_to_captured(&key(0), &MinHash::of(REPORT)), 0.0);
+        assert!(tracker.similarity_to_captured(&key(1), &MinHash::of(INBOX)) > DEFAULT_THRESHOLD);
+        assert!(capture(&mut tracker, &key(0), REPORT));
+    }
+}
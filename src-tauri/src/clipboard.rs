@@ -0,0 +1,723 @@
+/// Clipboard contents, richest representation first.
+/// Read by `platform::Platform::read_clipboard_content`.
+#[derive(Debug, Clone)]
+pub enum ClipboardContent {
+    /// Plain text only
+    Text(String),
+    /// HTML (web pages, Google Docs, Notion, …) with the plain-text fallback
+    Html { html: String, text: Option<String> },
+    /// RTF (TextEdit, Pages, Word, …) with the plain-text fallback
+    Rtf { rtf: String, text: Option<String> },
+    /// Bitmap with no accompanying text (screenshots, copied images)
+    Image(ClipboardImage),
+}
+
+/// One clipboard change, read as soon as the platform reported it
+#[derive(Debug, Clone)]
+pub struct ClipboardEvent {
+    /// Unix timestamp in milliseconds when the copy was observed
+    pub timestamp: i64,
+    pub content: ClipboardContent,
+    /// Window focused at the moment of the copy
+    pub window: Option<crate::platform::ActiveWindowInfo>,
+}
+
+/// Raw RGBA8 pixels from the clipboard
+#[derive(Debug, Clone)]
+pub struct ClipboardImage {
+    pub width: usize,
+    pub height: usize,
+    pub rgba: Vec<u8>,
+}
+
+impl ClipboardContent {
+    /// `capture_type` metadata value for this variant
+    pub fn capture_type(&self) -> &'static str {
+        match self {
+            ClipboardContent::Text(_) => "clipboard",
+            ClipboardContent::Html { .. } => "clipboard_html",
+            ClipboardContent::Rtf { .. } => "clipboard_rtf",
+            ClipboardContent::Image(_) => "clipboard_image",
+        }
+    }
+
+    /// Cheap identity used to detect clipboard changes between polls
+    pub fn fingerprint(&self) -> u64 {
+        use std::hash::{Hash, Hasher};
+        let mut hasher = std::collections::hash_map::DefaultHasher::new();
+        match self {
+            ClipboardContent::Text(t) => t.hash(&mut hasher),
+            ClipboardContent::Html { html, .. } => html.hash(&mut hasher),
+            ClipboardContent::Rtf { rtf, .. } => rtf.hash(&mut hasher),
+            ClipboardContent::Image(img) => {
+                (img.width, img.height).hash(&mut hasher);
+                img.rgba.hash(&mut hasher);
+            }
+        }
+        hasher.finish()
+    }
+
+    /// Text to capture: Markdown for rich formats, plain text otherwise.
+    /// Images have no text until they have been through OCR.
+    pub fn to_markdown(&self) -> Option<String> {
+        match self {
+            ClipboardContent::Text(t) => Some(t.clone()),
+            ClipboardContent::Html { html, text } => {
+                let md = html_to_markdown(html);
+                if md.trim().is_empty() { text.clone() } else { Some(md) }
+            }
+            ClipboardContent::Rtf { rtf, text } => {
+                let md = rtf_to_markdown(rtf);
+                if md.trim().is_empty() { text.clone() } else { Some(md) }
+            }
+            ClipboardContent::Image(_) => None,
+        }
+    }
+}
+
+impl ClipboardImage {
+    /// Encode as PNG (for OCR helpers and attachments)
+    pub fn to_png(&self) -> Result<Vec<u8>, String> {
+        let buffer = image::RgbaImage::from_raw(self.width as u32, self.height as u32, self.rgba.clone())
+            .ok_or("Clipboard image has an invalid size")?;
+        let mut out = std::io::Cursor::new(Vec::new());
+        buffer
+            .write_to(&mut out, image::ImageFormat::Png)
+            .map_err(|e| format!("PNG encode error: {}", e))?;
+        Ok(out.into_inner())
+    }
+}
+
+/// Read the richest clipboard representation via arboard.
+/// `read_rtf` is the platform's RTF reader (arboard has no RTF support).
+pub fn read_content(read_rtf: impl FnOnce() -> Option<String>) -> Option<ClipboardContent> {
+    let mut clipboard = arboard::Clipboard::new().ok()?;
+    let text = clipboard.get_text().ok().filter(|t| !t.trim().is_empty());
+
+    if let Ok(html) = clipboard.get().html() {
+        if !html.trim().is_empty() {
+            return Some(ClipboardContent::Html { html, text });
+        }
+    }
+    if let Some(rtf) = read_rtf().filter(|r| r.starts_with("{\\rtf")) {
+        return Some(ClipboardContent::Rtf { rtf, text });
+    }
+    if let Some(text) = text {
+        return Some(ClipboardContent::Text(text));
+    }
+    let image = clipboard.get_image().ok()?;
+    Some(ClipboardContent::Image(ClipboardImage {
+        width: image.width,
+        height: image.height,
+        rgba: image.bytes.into_owned(),
+    }))
+}
+
+/// Build the change callback: read the clipboard, skip repeats of the same
+/// content, and send everything else as an event.
+fn make_emitter(
+    platform: crate::platform::SharedPlatform,
+    tx: tokio::sync::mpsc::UnboundedSender<ClipboardEvent>,
+    last_fingerprint: std::sync::Arc<std::sync::atomic::AtomicU64>,
+) -> impl Fn() + Send + Clone + 'static {
+    move || {
+        let content = match platform.read_clipboard_content() {
+            Some(c) => c,
+            None => return,
+        };
+        let fingerprint = content.fingerprint();
+        if last_fingerprint.swap(fingerprint, std::sync::atomic::Ordering::SeqCst) == fingerprint {
+            return;
+        }
+        let timestamp = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_millis() as i64;
+        let _ = tx.send(ClipboardEvent {
+            timestamp,
+            content,
+            window: platform.active_window(),
+        });
+    }
+}
+
+/// Watch the clipboard on a dedicated thread and deliver every distinct copy.
+/// Falls back to polling once a second if the platform watcher is unavailable
+/// (e.g. a Wayland compositor without data-control and no XWayland).
+pub fn spawn_watcher(
+    platform: crate::platform::SharedPlatform,
+    tx: tokio::sync::mpsc::UnboundedSender<ClipboardEvent>,
+) {
+    // Seed with the current content so pre-existing clipboard text is not captured
+    let initial = platform
+        .read_clipboard_content()
+        .map(|c| c.fingerprint())
+        .unwrap_or(0);
+    let last_fingerprint = std::sync::Arc::new(std::sync::atomic::AtomicU64::new(initial));
+    let emit = make_emitter(platform, tx, last_fingerprint);
+
+    std::thread::spawn(move || {
+        if crate::platform::platform_watch_clipboard(Box::new(emit.clone())).is_err() {
+            loop {
+                std::thread::sleep(std::time::Duration::from_secs(1));
+                emit();
+            }
+        }
+    });
+}
+
+// ── HTML → Markdown ─────────────────────────────────────────────────────────
+
+/// Decode the common HTML entities
+fn decode_entities(s: &str) -> String {
+    if !s.contains('&') {
+        return s.to_string();
+    }
+    let mut out = String::with_capacity(s.len());
+    let mut rest = s;
+    while let Some(pos) = rest.find('&') {
+        out.push_str(&rest[..pos]);
+        rest = &rest[pos..];
+        // Entities are short; look for the ';' within the next 12 chars
+        let end = match rest.char_indices().take(12).find(|(_, c)| *c == ';') {
+            Some((e, _)) => e,
+            None => {
+                out.push('&');
+                rest = &rest[1..];
+                continue;
+            }
+        };
+        let entity = &rest[1..end];
+        let decoded = match entity {
+            "amp" => Some('&'),
+            "lt" => Some('<'),
+            "gt" => Some('>'),
+            "quot" => Some('"'),
+            "apos" | "#39" => Some('\''),
+            "nbsp" => Some(' '),
+            _ if entity.starts_with("#x") || entity.starts_with("#X") => {
+                u32::from_str_radix(&entity[2..], 16).ok().and_then(char::from_u32)
+            }
+            _ if entity.starts_with('#') => entity[1..].parse().ok().and_then(char::from_u32),
+            _ => None,
+        };
+        match decoded {
+            Some(c) => {
+                out.push(c);
+                rest = &rest[end + 1..];
+            }
+            None => {
+                out.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+/// Value of an attribute inside a tag body, e.g. `href` in `a href="…"`
+fn tag_attr(tag: &str, name: &str) -> Option<String> {
+    let lower = tag.to_ascii_lowercase();
+    let mut from = 0;
+    while let Some(pos) = lower[from..].find(name) {
+        let start = from + pos;
+        from = start + name.len();
+        let before_ok = start == 0 || lower.as_bytes()[start - 1].is_ascii_whitespace();
+        let after = lower[from..].trim_start();
+        if !before_ok || !after.starts_with('=') {
+            continue;
+        }
+        let value_start = tag.len() - after.len() + 1;
+        let value = tag[value_start..].trim_start();
+        let (quote, body) = match value.chars().next() {
+            Some(q @ ('"' | '\'')) => (Some(q), &value[1..]),
+            _ => (None, value),
+        };
+        let end = match quote {
+            Some(q) => body.find(q).unwrap_or(body.len()),
+            None => body.find(|c: char| c.is_whitespace() || c == '>').unwrap_or(body.len()),
+        };
+        return Some(decode_entities(&body[..end]));
+    }
+    None
+}
+
+/// Convert clipboard HTML to Markdown, keeping links, emphasis, headings, lists,
+/// quotes, code and table rows. Unknown tags are dropped, their text kept.
+pub fn html_to_markdown(html: &str) -> String {
+    // Windows CF_HTML may still carry its "Version:…StartHTML:…" header
+    let html = match html.find('<') {
+        Some(pos) if html.starts_with("Version:") => &html[pos..],
+        _ => html,
+    };
+
+    let mut out = String::new();
+    let mut link_stack: Vec<(usize, Option<String>)> = Vec::new();
+    let mut list_stack: Vec<Option<usize>> = Vec::new(); // None = bullet, Some(n) = ordered
+    let mut skip_depth = 0usize; // inside <script>/<style>/<head>
+    let mut pre_depth = 0usize;
+    let mut quote_depth = 0usize;
+    let mut row_has_cell = false;
+
+    let push_break = |out: &mut String, blank: bool| {
+        while out.ends_with(' ') { out.pop(); }
+        if out.is_empty() { return; }
+        let wanted = if blank { "\n\n" } else { "\n" };
+        if !out.ends_with(wanted) {
+            if blank && out.ends_with('\n') { out.push('\n'); } else { out.push_str(wanted); }
+        }
+    };
+
+    let mut rest = html;
+    while !rest.is_empty() {
+        // Text up to the next tag
+        let text_end = rest.find('<').unwrap_or(rest.len());
+        if text_end > 0 {
+            if skip_depth == 0 {
+                let text = decode_entities(&rest[..text_end]);
+                if pre_depth > 0 {
+                    out.push_str(&text);
+                } else {
+                    for (i, word) in text.split_whitespace().enumerate() {
+                        let starts_with_space = i == 0 && text.starts_with(char::is_whitespace);
+                        if (i > 0 || starts_with_space) && !out.ends_with([' ', '\n']) && !out.is_empty() {
+                            out.push(' ');
+                        }
+                        if out.ends_with('\n') && quote_depth > 0 && !out.ends_with("> ") {
+                            out.push_str(&"> ".repeat(quote_depth));
+                        }
+                        out.push_str(word);
+                    }
+                    // Whitespace between inline tags still separates words
+                    if text.ends_with(char::is_whitespace) && !out.is_empty() && !out.ends_with([' ', '\n']) {
+                        out.push(' ');
+                    }
+                }
+            }
+            rest = &rest[text_end..];
+            continue;
+        }
+
+        // Comments
+        if rest.starts_with("<!--") {
+            rest = match rest.find("-->") {
+                Some(end) => &rest[end + 3..],
+                None => "",
+            };
+            continue;
+        }
+
+        let tag_end = match rest.find('>') {
+            Some(e) => e,
+            None => break,
+        };
+        let tag = &rest[1..tag_end];
+        rest = &rest[tag_end + 1..];
+
+        let closing = tag.starts_with('/');
+        let body = tag.trim_start_matches('/');
+        let name: String = body
+            .chars()
+            .take_while(|c| c.is_ascii_alphanumeric())
+            .collect::<String>()
+            .to_lowercase();
+
+        match name.as_str() {
+            "script" | "style" | "head" | "title" => {
+                if closing { skip_depth = skip_depth.saturating_sub(1); } else { skip_depth += 1; }
+            }
+            _ if skip_depth > 0 => {}
+            "br" => push_break(&mut out, false),
+            "p" | "div" | "section" | "article" | "header" | "footer" | "table" => push_break(&mut out, true),
+            "h1" | "h2" | "h3" | "h4" | "h5" | "h6" => {
+                push_break(&mut out, true);
+                if !closing {
+                    let level = name[1..].parse::<usize>().unwrap_or(1);
+                    out.push_str(&"#".repeat(level));
+                    out.push(' ');
+                }
+            }
+            "ul" | "ol" => {
+                if closing {
+                    list_stack.pop();
+                } else {
+                    list_stack.push(if name == "ol" { Some(0) } else { None });
+                }
+                push_break(&mut out, list_stack.is_empty());
+            }
+            "li" if !closing => {
+                push_break(&mut out, false);
+                let indent = "  ".repeat(list_stack.len().saturating_sub(1));
+                out.push_str(&indent);
+                match list_stack.last_mut() {
+                    Some(Some(n)) => {
+                        *n += 1;
+                        out.push_str(&format!("{}. ", n));
+                    }
+                    _ => out.push_str("- "),
+                }
+            }
+            "blockquote" => {
+                push_break(&mut out, true);
+                if closing { quote_depth = quote_depth.saturating_sub(1); } else {
+                    quote_depth += 1;
+                    out.push_str(&"> ".repeat(quote_depth));
+                }
+            }
+            "pre" => {
+                push_break(&mut out, true);
+                if closing {
+                    pre_depth = pre_depth.saturating_sub(1);
+                    out.push_str("```");
+                    push_break(&mut out, true);
+                } else {
+                    pre_depth += 1;
+                    out.push_str("```\n");
+                }
+            }
+            "code" if pre_depth == 0 => out.push('`'),
+            "b" | "strong" => out.push_str("**"),
+            "i" | "em" => out.push('*'),
+            "s" | "del" | "strike" => out.push_str("~~"),
+            "tr" => {
+                if closing {
+                    if row_has_cell { out.push_str(" |"); }
+                    row_has_cell = false;
+                }
+                push_break(&mut out, false);
+            }
+            "td" | "th" if !closing => {
+                out.push_str(if row_has_cell { " | " } else { "| " });
+                row_has_cell = true;
+            }
+            "a" => {
+                if closing {
+                    if let Some((start, Some(href))) = link_stack.pop() {
+                        let label = out[start..].trim().to_string();
+                        out.truncate(start);
+                        if label.is_empty() {
+                            out.push_str(&format!("<{}>", href));
+                        } else if label == href {
+                            out.push_str(&label);
+                        } else {
+                            out.push_str(&format!("[{}]({})", label, href));
+                        }
+                    }
+                } else {
+                    let href = tag_attr(body, "href")
+                        .filter(|h| !h.is_empty() && !h.starts_with('#') && !h.starts_with("javascript:"));
+                    link_stack.push((out.len(), href));
+                }
+            }
+            "img" => {
+                if let Some(alt) = tag_attr(body, "alt").filter(|a| !a.trim().is_empty()) {
+                    out.push_str(&format!("[image: {}]", alt.trim()));
+                }
+            }
+            _ => {}
+        }
+    }
+
+    // Tidy: trim line ends and collapse runs of blank lines
+    let mut tidy = String::with_capacity(out.len());
+    let mut blank_run = 0;
+    for line in out.lines() {
+        let line = line.trim_end();
+        if line.is_empty() {
+            blank_run += 1;
+            if blank_run > 1 { continue; }
+        } else {
+            blank_run = 0;
+        }
+        tidy.push_str(line);
+        tidy.push('\n');
+    }
+    tidy.trim().to_string()
+}
+
+// ── RTF → Markdown ──────────────────────────────────────────────────────────
+
+/// Destinations whose content is not document text
+const RTF_SKIP_DESTINATIONS: &[&str] = &[
+    "fonttbl", "colortbl", "stylesheet", "info", "pict", "header", "footer",
+    "listtable", "listoverridetable", "rsidtbl", "generator", "xmlnstbl", "themedata",
+    "colorschememapping", "datastore", "latentstyles", "filetbl", "revtbl",
+];
+
+/// Convert clipboard RTF to Markdown: paragraphs, bold/italic and HYPERLINK
+/// fields survive; formatting tables and embedded pictures are dropped.
+pub fn rtf_to_markdown(rtf: &str) -> String {
+    struct Group {
+        skip: bool,
+        bold: bool,
+        italic: bool,
+        /// Set inside `{\*\fldinst HYPERLINK "…"}`
+        fldinst: bool,
+        /// Set inside `{\fldrslt …}` — the visible link text
+        fldrslt: bool,
+    }
+
+    let mut out = String::new();
+    let mut stack: Vec<Group> = vec![Group { skip: false, bold: false, italic: false, fldinst: false, fldrslt: false }];
+    let mut field_instruction = String::new();
+    let mut link: Option<(String, usize)> = None; // (url, start of link text in out)
+    let mut uc_skip = 1usize;
+    let mut pending_skip = 0usize;
+
+    let bytes = rtf.as_bytes();
+    let mut i = 0;
+    while i < bytes.len() {
+        let c = bytes[i];
+        match c {
+            b'{' => {
+                let top = stack.last().map(|g| (g.skip, g.bold, g.italic, g.fldinst, g.fldrslt))
+                    .unwrap_or((false, false, false, false, false));
+                stack.push(Group { skip: top.0, bold: top.1, italic: top.2, fldinst: top.3, fldrslt: top.4 });
+                i += 1;
+            }
+            b'}' => {
+                if let Some(g) = stack.pop() {
+                    if g.fldrslt && !stack.last().map(|p| p.fldrslt).unwrap_or(false) {
+                        if let Some((url, start)) = link.take() {
+                            let label = out[start..].trim().to_string();
+                            out.truncate(start);
+                            if label.is_empty() || label == url {
+                                out.push_str(&url);
+                            } else {
+                                out.push_str(&format!("[{}]({})", label, url));
+                            }
+                        }
+                    }
+                    if g.fldinst && !stack.last().map(|p| p.fldinst).unwrap_or(false) {
+                        let instr = field_instruction.trim().to_string();
+                        field_instruction.clear();
+                        if let Some(rest) = instr.strip_prefix("HYPERLINK") {
+                            let url = rest.trim().trim_matches('"').split('"').next().unwrap_or("").to_string();
+                            if !url.is_empty() {
+                                link = Some((url, out.len()));
+                            }
+                        }
+                    }
+                    // Close emphasis opened inside this group
+                    if let Some(parent) = stack.last() {
+                        if g.bold && !parent.bold && !g.skip { out.push_str("**"); }
+                        if g.italic && !parent.italic && !g.skip { out.push('*'); }
+                    }
+                }
+                i += 1;
+            }
+            b'\\' => {
+                i += 1;
+                if i >= bytes.len() { break; }
+                let next = bytes[i];
+                if next.is_ascii_alphabetic() {
+                    // Control word + optional numeric parameter
+                    let start = i;
+                    while i < bytes.len() && bytes[i].is_ascii_alphabetic() { i += 1; }
+                    let word = &rtf[start..i];
+                    let param_start = i;
+                    if i < bytes.len() && (bytes[i] == b'-' || bytes[i].is_ascii_digit()) {
+                        i += 1;
+                        while i < bytes.len() && bytes[i].is_ascii_digit() { i += 1; }
+                    }
+                    let param: Option<i32> = rtf[param_start..i].parse().ok();
+                    if i < bytes.len() && bytes[i] == b' ' { i += 1; }
+
+                    let group = match stack.last_mut() {
+                        Some(g) => g,
+                        None => break,
+                    };
+                    if RTF_SKIP_DESTINATIONS.contains(&word) {
+                        group.skip = true;
+                        continue;
+                    }
+                    if group.skip { continue; }
+                    match word {
+                        "par" | "line" | "sect" | "page" => {
+                            while out.ends_with(' ') { out.pop(); }
+                            out.push('\n');
+                        }
+                        "row" => out.push('\n'),
+                        "cell" => out.push_str(" | "),
+                        "tab" => out.push('\t'),
+                        "emdash" => out.push('—'),
+                        "endash" => out.push('–'),
+                        "bullet" => out.push('•'),
+                        "lquote" => out.push('‘'),
+                        "rquote" => out.push('’'),
+                        "ldblquote" => out.push('“'),
+                        "rdblquote" => out.push('”'),
+                        "fldinst" => group.fldinst = true,
+                        "fldrslt" => group.fldrslt = true,
+                        "uc" => uc_skip = param.unwrap_or(1).max(0) as usize,
+                        "u" => {
+                            if let Some(p) = param {
+                                let code = if p < 0 { (p + 65536) as u32 } else { p as u32 };
+                                if let Some(ch) = char::from_u32(code) {
+                                    if group.fldinst { field_instruction.push(ch); } else { out.push(ch); }
+                                }
+                                pending_skip = uc_skip;
+                            }
+                        }
+                        "b" => {
+                            let on = param != Some(0);
+                            if on != group.bold { out.push_str("**"); group.bold = on; }
+                        }
+                        "i" => {
+                            let on = param != Some(0);
+                            if on != group.italic { out.push('*'); group.italic = on; }
+                        }
+                        "plain" => {
+                            if group.bold { out.push_str("**"); group.bold = false; }
+                            if group.italic { out.push('*'); group.italic = false; }
+                        }
+                        _ => {}
+                    }
+                } else if next == b'*' {
+                    // Optional destination: skip unless it is a field instruction
+                    let is_fldinst = rtf[i + 1..].trim_start().starts_with("\\fldinst");
+                    if let Some(g) = stack.last_mut() {
+                        if !is_fldinst { g.skip = true; }
+                    }
+                    i += 1;
+                } else if next == b'\'' {
+                    // \'hh — one byte in the document code page (treated as Windows-1252/Latin-1)
+                    let is_hex = bytes.get(i + 1..i + 3).is_some_and(|h| h.iter().all(u8::is_ascii_hexdigit));
+                    if !is_hex {
+                        // Malformed escape: drop the quote, keep whatever follows as text
+                        i += 1;
+                        continue;
+                    }
+                    let hex = &rtf[i + 1..i + 3];
+                    i += 3;
+                    if pending_skip > 0 {
+                        pending_skip -= 1;
+                        continue;
+                    }
+                    let b = u8::from_str_radix(hex, 16).unwrap_or(b'?');
+                    if let Some(g) = stack.last() {
+                        if !g.skip {
+                            let ch = b as char;
+                            if g.fldinst { field_instruction.push(ch); } else { out.push(ch); }
+                        }
+                    }
+                } else {
+                    // Escaped literal: \\ \{ \} or control symbol
+                    let skip = stack.last().map(|g| g.skip).unwrap_or(true);
+                    if !skip {
+                        match next {
+                            b'\\' | b'{' | b'}' => out.push(next as char),
+                            b'~' => out.push(' '),
+                            b'\n' | b'\r' => out.push('\n'),
+                            _ => {}
+                        }
+                    }
+                    // Step over the whole symbol so a backslash before non-ASCII text stays on a char boundary
+                    i += rtf[i..].chars().next().map(char::len_utf8).unwrap_or(1);
+                }
+            }
+            b'\r' | b'\n' => i += 1,
+            _ => {
+                // Plain text run
+                let start = i;
+                while i < bytes.len() && !matches!(bytes[i], b'{' | b'}' | b'\\' | b'\r' | b'\n') { i += 1; }
+                let mut text = &rtf[start..i];
+                while pending_skip > 0 && !text.is_empty() {
+                    let len = text.chars().next().map(|c| c.len_utf8()).unwrap_or(1);
+                    text = &text[len..];
+                    pending_skip -= 1;
+                }
+                if let Some(g) = stack.last() {
+                    if !g.skip {
+                        if g.fldinst { field_instruction.push_str(text); } else { out.push_str(text); }
+                    }
+                }
+            }
+        }
+    }
+
+    out.lines()
+        .map(|l| l.trim_end())
+        .collect::<Vec<_>>()
+        .join("\n")
+        .trim()
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::platform::fake::FakePlatform;
+
+    #[test]
+    fn emits_each_distinct_copy_once_with_its_window() {
+        let platform = std::sync::Arc::new(FakePlatform::default());
+        let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+        let last = std::sync::Arc::new(std::sync::atomic::AtomicU64::new(0));
+        let emit = make_emitter(platform.clone(), tx, last);
+
+        platform.focus("Slack", "general");
+        platform.set_clipboard(ClipboardContent::Text("first copy".to_string()));
+        emit();
+        emit();
+        platform.focus("Notes", "Ideas");
+        platform.set_clipboard(ClipboardContent::Text("second copy".to_string()));
+        emit();
+
+        let first = rx.try_recv().expect("first copy");
+        assert!(matches!(first.content, ClipboardContent::Text(ref t) if t == "first copy"));
+        assert_eq!(first.window.map(|w| w.app_name).as_deref(), Some("Slack"));
+        let second = rx.try_recv().expect("second copy");
+        assert_eq!(second.window.map(|w| w.app_name).as_deref(), Some("Notes"));
+        assert!(rx.try_recv().is_err());
+    }
+
+    #[test]
+    fn decodes_entities_next_to_non_ascii_text() {
+        assert_eq!(decode_entities("Tom &amp;中文文本"), "Tom &中文文本");
+        assert_eq!(decode_entities("R&D 中文文本中文文本"), "R&D 中文文本中文文本");
+        assert_eq!(decode_entities("&lt;b&gt; caf&#233; &#x4e2d;"), "<b> café 中");
+    }
+
+    #[test]
+    fn converts_html_structure_to_markdown() {
+        let html = "Version:0.9\r\nStartHTML:0\r\n<html><head><style>p{}</style></head><body>\
+            <h2>Plan</h2><p>See <a href=\"https://example.com/a?b=1&amp;c=2\">the doc</a> and \
+            <a href=\"https://example.com\">https://example.com</a>.</p>\
+            <ul><li>one</li><li>two<ol><li>nested</li></ol></li></ul>\
+            <p><b>bold</b> <em>it</em> <code>x</code></p></body></html>";
+        assert_eq!(
+            html_to_markdown(html),
+            "## Plan\n\nSee [the doc](https://example.com/a?b=1&c=2) and https://example.com.\n\n\
+             - one\n- two\n  1. nested\n\n**bold** *it* `x`"
+        );
+    }
+
+    #[test]
+    fn html_conversion_survives_non_ascii_and_broken_markup() {
+        assert_eq!(html_to_markdown("<p>café 中文 &eacute;</p>"), "café 中文 &eacute;");
+        assert_eq!(html_to_markdown("<p>unterminated <a href=\"x"), "unterminated");
+        assert_eq!(html_to_markdown("</li></ul><a>orphan</a> <!-- open"), "orphan");
+    }
+
+    #[test]
+    fn converts_rtf_text_links_and_escapes_to_markdown() {
+        let rtf = r#"{\rtf1\ansi{\fonttbl{\f0 Helvetica;}}\f0 Caf\'e9 \b bold\b0  and \i it\i0\par
+{\field{\*\fldinst HYPERLINK "https://example.com"}{\fldrslt the site}}\par
+\uc1\u20013?\u25991? \{braces\}}"#;
+        assert_eq!(
+            rtf_to_markdown(rtf),
+            "Café **bold** and *it*\n[the site](https://example.com)\n中文 {braces}"
+        );
+    }
+
+    #[test]
+    fn rtf_escapes_next_to_non_ascii_text_do_not_panic() {
+        assert_eq!(rtf_to_markdown(r"{\rtf1 caf\é}"), "caf");
+        assert_eq!(rtf_to_markdown(r"{\rtf1 \'aé more}"), "aé more");
+        assert_eq!(rtf_to_markdown(r"{\rtf1 end\'"), "end");
+        assert_eq!(rtf_to_markdown(r"{\rtf1 \u-3"), "\u{fffd}");
+        assert_eq!(rtf_to_markdown("}}{\\"), "");
+    }
+}
@@ -0,0 +1,217 @@
+use crate::similarity;
+use serde::{Deserialize, Serialize};
+use tauri_plugin_store::StoreExt;
+
+/// Store file holding fingerprints of recent uploads
+const FINGERPRINT_STORE: &str = "capture_fingerprints.json";
+
+/// Passive capture sources whose near-duplicates are dropped. Manual saves are
+/// always sent, though they still suppress later passive copies.
+pub const PASSIVE_SOURCES: &[&str] = &["screen", "clipboard", "writing"];
+
+/// Serializes check-and-record of fingerprints (captures are sent from spawned tasks)
+static FINGERPRINT_LOCK: std::sync::Mutex<()> = std::sync::Mutex::new(());
+
+/// Near-duplicate settings (config.json → "dedup")
+#[derive(Debug, Serialize, Deserialize, Clone, Copy)]
+#[serde(default)]
+pub struct DedupSettings {
+    /// How long an upload suppresses its duplicates. 0 disables deduplication.
+    pub window_minutes: u32,
+    /// Maximum number of differing SimHash bits (of 64) for two texts to count
+    /// as the same. Unrelated texts differ in about 32; OCR re-reads of the
+    /// same text in 4–10. 0 only matches identical wording.
+    pub max_distance: u32,
+    /// Share of a text's shingles that must appear in one earlier upload for
+    /// it to count as part of it, e.g. a copied paragraph of a screen already
+    /// read. The whole-text SimHash misses these. 0 disables the check.
+    pub min_containment: f64,
+}
+
+impl Default for DedupSettings {
+    fn default() -> Self {
+        Self { window_minutes: 120, max_distance: 10, min_containment: 0.8 }
+    }
+}
+
+/// Fingerprint of one upload
+#[derive(Debug, Serialize, Deserialize, Clone)]
+struct FingerprintEntry {
+    simhash: u64,
+    /// Absent from entries written before containment checks
+    #[serde(default)]
+    sketch: similarity::Sketch,
+    source: String,
+    /// Unix timestamp in seconds
+    timestamp: i64,
+}
+
+pub fn load_settings(app: &tauri::AppHandle) -> DedupSettings {
+    app.store("config.json")
+        .ok()
+        .and_then(|s| s.get("dedup"))
+        .and_then(|v| serde_json::from_value(v).ok())
+        .unwrap_or_default()
+}
+
+pub fn save_settings(app: &tauri::AppHandle, settings: DedupSettings) -> Result<(), String> {
+    let settings = DedupSettings {
+        max_distance: settings.max_distance.min(32),
+        min_containment: settings.min_containment.clamp(0.0, 1.0),
+        ..settings
+    };
+    let store = app.store("config.json").map_err(|e| e.to_string())?;
+    store.set("dedup", serde_json::json!(settings));
+    store.save().map_err(|e| e.to_string())
+}
+
+fn now_secs() -> i64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs() as i64
+}
+
+/// 64-bit SimHash over the text's character shingles (see `similarity`).
+/// Texts that share most shingles differ in few bits, whichever stream they
+/// came from (clipboard, OCR, writing).
+pub fn simhash(text: &str) -> u64 {
+    let mut weights = [0i32; 64];
+    for hash in similarity::shingles(text) {
+        for (bit, weight) in weights.iter_mut().enumerate() {
+            if (hash >> bit) & 1 == 1 { *weight += 1 } else { *weight -= 1 }
+        }
+    }
+    weights
+        .iter()
+        .enumerate()
+        .filter(|(_, w)| **w > 0)
+        .fold(0u64, |acc, (bit, _)| acc | 1 << bit)
+}
+
+/// Whether a text with this fingerprint and sketch repeats an earlier upload:
+/// it reads the same overall, or is (mostly) a passage of it. A longer text
+/// that merely contains an earlier one is new content and is not a duplicate.
+fn repeats(earlier: &FingerprintEntry, simhash: u64, sketch: &similarity::Sketch, settings: &DedupSettings) -> bool {
+    (earlier.simhash ^ simhash).count_ones() <= settings.max_distance
+        || (settings.min_containment > 0.0
+            && sketch
+                .contained_in(&earlier.sketch)
+                .is_some_and(|share| share >= settings.min_containment))
+}
+
+fn load_entries(app: &tauri::AppHandle) -> Vec<FingerprintEntry> {
+    app.store(FINGERPRINT_STORE)
+        .ok()
+        .and_then(|s| s.get("entries"))
+        .and_then(|v| serde_json::from_value(v).ok())
+        .unwrap_or_default()
+}
+
+fn save_entries(app: &tauri::AppHandle, entries: &[FingerprintEntry]) {
+    if let Ok(store) = app.store(FINGERPRINT_STORE) {
+        store.set("entries", serde_json::json!(entries));
+        let _ = store.save();
+    }
+}
+
+/// Check `text` against recent uploads from every source and claim its
+/// fingerprint if it is new. Returns the source of the earlier upload when
+/// it is a near-duplicate. Call `release` if the upload then fails.
+pub fn check_and_claim(app: &tauri::AppHandle, text: &str, source: &str) -> Result<u64, String> {
+    let settings = load_settings(app);
+    let fingerprint = simhash(text);
+    if settings.window_minutes == 0 {
+        return Ok(fingerprint);
+    }
+    let sketch = similarity::Sketch::of(text);
+
+    let _guard = FINGERPRINT_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let now = now_secs();
+    let cutoff = now - settings.window_minutes as i64 * 60;
+    let mut entries = load_entries(app);
+    entries.retain(|e| e.timestamp >= cutoff);
+
+    if let Some(earlier) = entries.iter().find(|e| repeats(e, fingerprint, &sketch, &settings)) {
+        return Err(earlier.source.clone());
+    }
+
+    entries.push(FingerprintEntry { simhash: fingerprint, sketch, source: source.to_string(), timestamp: now });
+    save_entries(app, &entries);
+    Ok(fingerprint)
+}
+
+/// Forget a claimed fingerprint, e.g. after a failed upload
+pub fn release(app: &tauri::AppHandle, fingerprint: u64) {
+    let _guard = FINGERPRINT_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let mut entries = load_entries(app);
+    if let Some(pos) = entries.iter().rposition(|e| e.simhash == fingerprint) {
+        entries.remove(pos);
+        save_entries(app, &entries);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SCREEN: &str = "Inbox - Outlook\nQ3 planning follow-up\nFrom: Dana Whitfield\n\
+        Thanks everyone for joining today. As discussed, we will move the\n\
+        launch of the reporting dashboard to the second week of October so the\n\
+        data team can finish the migration to the new warehouse first.\n\
+        Please send me your updated estimates by Friday, and flag anything that\n\
+        depends on the old export jobs. Marketing will hold the announcement\n\
+        until we confirm the date. I have attached the revised roadmap and the\n\
+        notes from the call.\nReply  Reply all  Forward";
+
+    /// The same passage as copied from the email, without OCR line breaks
+    const PARAGRAPH: &str = "As discussed, we will move the launch of the reporting dashboard \
+        to the second week of October so the data team can finish the migration.";
+
+    fn entry(text: &str) -> FingerprintEntry {
+        FingerprintEntry {
+            simhash: simhash(text),
+            sketch: similarity::Sketch::of(text),
+            source: "ocr".to_string(),
+            timestamp: 0,
+        }
+    }
+
+    fn repeats_text(earlier: &str, text: &str, settings: &DedupSettings) -> bool {
+        repeats(&entry(earlier), simhash(text), &similarity::Sketch::of(text), settings)
+    }
+
+    #[test]
+    fn paragraph_of_an_uploaded_screen_is_a_duplicate() {
+        let settings = DedupSettings::default();
+        // Too far apart for the whole-text fingerprint alone
+        assert!((simhash(SCREEN) ^ simhash(PARAGRAPH)).count_ones() > settings.max_distance);
+        assert!(repeats_text(SCREEN, PARAGRAPH, &settings));
+        let disabled = DedupSettings { min_containment: 0.0, ..settings };
+        assert!(!repeats_text(SCREEN, PARAGRAPH, &disabled));
+    }
+
+    #[test]
+    fn text_around_an_uploaded_paragraph_is_new() {
+        let settings = DedupSettings::default();
+        assert!(!repeats_text(PARAGRAPH, SCREEN, &settings));
+        let other = "Reminder: the office will be closed on Monday for maintenance of the \
+            heating system. Please take your laptops home on Friday evening.";
+        assert!(!repeats_text(SCREEN, other, &settings));
+        // Short snippets are left to the whole-text fingerprint
+        assert!(!repeats_text(SCREEN, "second week of October", &settings));
+    }
+
+    #[test]
+    fn reworded_text_is_a_duplicate() {
+        let reread = SCREEN.replace("Thanks everyone", "Thanks every0ne").replace('\n', " ");
+        assert!(repeats_text(SCREEN, &reread, &DedupSettings::default()));
+    }
+
+    #[test]
+    fn entries_without_a_sketch_still_load() {
+        let old: FingerprintEntry =
+            serde_json::from_value(serde_json::json!({ "simhash": 1, "source": "clipboard", "timestamp": 5 })).unwrap();
+        assert_eq!(old.sketch, similarity::Sketch::default());
+    }
+}
@@ -0,0 +1,343 @@
+/// Longest token sequence compared; the LCS table is `MAX_TOKENS²` cells
+const MAX_TOKENS: usize = 1200;
+
+/// Inserted runs shorter than this are cursor-line jitter or small edits
+/// and deleted runs shorter than this are ignored for the same reason
+const MIN_INSERT_RUN: usize = 3;
+
+/// Below this share of matched tokens the screen shows different content
+/// altogether (new page, new document) — nothing in it counts as writing
+const MIN_MATCH_RATIO: f64 = 0.3;
+
+/// A replaced run this similar to what it replaced is the same text re-read
+const REREAD_SIMILARITY: f64 = 0.6;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Scroll {
+    /// Text left at the top, new text revealed at the bottom
+    Down,
+    /// Text left at the bottom, new text revealed at the top
+    Up,
+}
+
+/// What changed between two snapshots
+#[derive(Debug, Clone, Default)]
+pub struct WritingDelta {
+    /// Newly written text, one inserted run per line
+    pub text: String,
+    pub inserted_words: usize,
+    /// Text that was deleted (not scrolled away), one run per line
+    pub removed: String,
+    pub scroll: Option<Scroll>,
+}
+
+struct Token<'a> {
+    word: &'a str,
+    norm: String,
+    /// `norm` split up front, so comparing tokens doesn't allocate
+    chars: Vec<char>,
+}
+
+/// Words with punctuation stripped and lowercased. Common OCR confusions are
+/// folded so that "modern"/"modem"-style misreads compare equal.
+fn tokenize(text: &str) -> Vec<Token<'_>> {
+    text.split_whitespace()
+        .filter_map(|word| {
+            let norm: String = word
+                .chars()
+                .filter(|c| c.is_alphanumeric())
+                .flat_map(|c| c.to_lowercase())
+                .map(|c| match c {
+                    '0' => 'o',
+                    '1' | 'i' => 'l',
+                    _ => c,
+                })
+                .collect::<String>()
+                .replace("rn", "m");
+            if norm.is_empty() {
+                return None;
+            }
+            let chars = norm.chars().collect();
+            Some(Token { word, norm, chars })
+        })
+        .collect()
+}
+
+/// Reusable rows for `within_edit_distance`
+#[derive(Default)]
+struct EditRows {
+    prev: Vec<usize>,
+    cur: Vec<usize>,
+}
+
+/// Edit distance between `a` and `b`, stopping early once it exceeds `limit`
+fn within_edit_distance(a: &[char], b: &[char], limit: usize, rows: &mut EditRows) -> bool {
+    if a.len().abs_diff(b.len()) > limit {
+        return false;
+    }
+    let EditRows { prev, cur } = rows;
+    prev.clear();
+    prev.extend(0..=b.len());
+    cur.clear();
+    cur.resize(b.len() + 1, 0);
+    for i in 1..=a.len() {
+        cur[0] = i;
+        let mut row_min = cur[0];
+        for j in 1..=b.len() {
+            let cost = if a[i - 1] == b[j - 1] { 0 } else { 1 };
+            cur[j] = (prev[j] + 1).min(cur[j - 1] + 1).min(prev[j - 1] + cost);
+            row_min = row_min.min(cur[j]);
+        }
+        if row_min > limit {
+            return false;
+        }
+        std::mem::swap(prev, cur);
+    }
+    prev[b.len()] <= limit
+}
+
+/// Equal words, tolerating one misread character in 4+ letter words and two in 8+
+fn tokens_match(a: &Token, b: &Token, rows: &mut EditRows) -> bool {
+    if a.norm == b.norm {
+        return true;
+    }
+    let len = a.chars.len().max(b.chars.len());
+    let limit = if len >= 8 { 2 } else if len >= 4 { 1 } else { 0 };
+    limit > 0 && within_edit_distance(&a.chars, &b.chars, limit, rows)
+}
+
+/// Matched (previous, current) token index pairs, in order
+fn align(prev: &[Token], cur: &[Token]) -> Vec<(usize, usize)> {
+    // Exact common prefix/suffix first — the usual case is a small change
+    let mut head = 0;
+    while head < prev.len() && head < cur.len() && prev[head].norm == cur[head].norm {
+        head += 1;
+    }
+    let mut tail = 0;
+    while tail < prev.len() - head
+        && tail < cur.len() - head
+        && prev[prev.len() - 1 - tail].norm == cur[cur.len() - 1 - tail].norm
+    {
+        tail += 1;
+    }
+
+    let p = &prev[head..prev.len() - tail];
+    let c = &cur[head..cur.len() - tail];
+
+    // LCS over the middle, with fuzzy token equality
+    let mut rows = EditRows::default();
+    let width = c.len() + 1;
+    let mut table = vec![0u16; (p.len() + 1) * width];
+    for i in (0..p.len()).rev() {
+        for j in (0..c.len()).rev() {
+            table[i * width + j] = if tokens_match(&p[i], &c[j], &mut rows) {
+                table[(i + 1) * width + j + 1] + 1
+            } else {
+                table[(i + 1) * width + j].max(table[i * width + j + 1])
+            };
+        }
+    }
+
+    let mut pairs: Vec<(usize, usize)> = (0..head).map(|k| (k, k)).collect();
+    let (mut i, mut j) = (0, 0);
+    while i < p.len() && j < c.len() {
+        if tokens_match(&p[i], &c[j], &mut rows) {
+            pairs.push((head + i, head + j));
+            i += 1;
+            j += 1;
+        } else if table[(i + 1) * width + j] >= table[i * width + j + 1] {
+            i += 1;
+        } else {
+            j += 1;
+        }
+    }
+    pairs.extend((0..tail).map(|k| (prev.len() - tail + k, cur.len() - tail + k)));
+    pairs
+}
+
+/// Dice coefficient over character bigrams
+fn similarity(a: &str, b: &str) -> f64 {
+    let bigrams = |s: &str| -> Vec<(char, char)> {
+        let chars: Vec<char> = s.chars().collect();
+        chars.windows(2).map(|w| (w[0], w[1])).collect()
+    };
+    let a = bigrams(a);
+    let mut b = bigrams(b);
+    if a.is_empty() || b.is_empty() {
+        return 0.0;
+    }
+    let total = a.len() + b.len();
+    let mut shared = 0;
+    for bg in &a {
+        if let Some(pos) = b.iter().position(|x| x == bg) {
+            b.swap_remove(pos);
+            shared += 1;
+        }
+    }
+    2.0 * shared as f64 / total as f64
+}
+
+/// A gap between matched tokens: what was removed and what was inserted there
+struct Hunk {
+    deleted: std::ops::Range<usize>,
+    inserted: std::ops::Range<usize>,
+}
+
+fn hunks(pairs: &[(usize, usize)], prev_len: usize, cur_len: usize) -> Vec<Hunk> {
+    let mut out = Vec::new();
+    let (mut pi, mut ci) = (0, 0);
+    for &(i, j) in pairs.iter().chain(std::iter::once(&(prev_len, cur_len))) {
+        if i > pi || j > ci {
+            out.push(Hunk { deleted: pi..i, inserted: ci..j });
+        }
+        pi = i + 1;
+        ci = j + 1;
+    }
+    out
+}
+
+/// Find text written between two OCR snapshots of the same app.
+/// Compares words rather than lines, so reflowed paragraphs diff as unchanged
+/// and single-character OCR misreads still match.
+///
+/// Scrolling is recognised when a run of text disappears from one edge while
+/// new text appears at the other; the revealed text is not counted. Typing at
+/// the end of a document that also scrolls it is indistinguishable from a
+/// scroll and is not counted either.
+///
+/// The alignment is quadratic in the snapshot length (up to `MAX_TOKENS²`
+/// comparisons), so async callers should run this on a blocking thread.
+pub fn writing_delta(previous: &str, current: &str) -> WritingDelta {
+    let mut prev = tokenize(previous);
+    let mut cur = tokenize(current);
+    prev.truncate(MAX_TOKENS);
+    cur.truncate(MAX_TOKENS);
+    if prev.is_empty() || cur.is_empty() {
+        return WritingDelta::default();
+    }
+
+    let pairs = align(&prev, &cur);
+    if (pairs.len() as f64) < MIN_MATCH_RATIO * prev.len().min(cur.len()) as f64 {
+        return WritingDelta::default();
+    }
+
+    let hunks = hunks(&pairs, prev.len(), cur.len());
+    let at_start = |h: &Hunk| h.deleted.start == 0 && h.inserted.start == 0;
+    let at_end = |h: &Hunk| h.deleted.end == prev.len() && h.inserted.end == cur.len();
+    let scroll_min = (prev.len() / 10).max(4);
+
+    let leading = hunks.first().filter(|h| at_start(h));
+    let trailing = hunks.last().filter(|h| at_end(h));
+    let scroll = match (leading, trailing) {
+        (Some(l), Some(t)) if l.deleted.len() >= scroll_min && l.inserted.len() <= 2 && t.deleted.len() <= 2 => {
+            Some(Scroll::Down)
+        }
+        (Some(l), Some(t)) if t.deleted.len() >= scroll_min && t.inserted.len() <= 2 && l.deleted.len() <= 2 => {
+            Some(Scroll::Up)
+        }
+        _ => None,
+    };
+
+    let join = |tokens: &[Token]| tokens.iter().map(|t| t.word).collect::<Vec<_>>().join(" ");
+    let mut runs: Vec<String> = Vec::new();
+    let mut removed: Vec<String> = Vec::new();
+    let mut inserted_words = 0;
+    for (idx, hunk) in hunks.iter().enumerate() {
+        // Text that scrolled out of view or was revealed by the scroll
+        let edge = (idx == 0 && at_start(hunk)) || (idx == hunks.len() - 1 && at_end(hunk));
+        if scroll.is_some() && edge {
+            continue;
+        }
+        let deleted = &prev[hunk.deleted.clone()];
+        let inserted = &cur[hunk.inserted.clone()];
+        if !deleted.is_empty() && !inserted.is_empty() {
+            let old: String = deleted.iter().map(|t| t.norm.as_str()).collect();
+            let new: String = inserted.iter().map(|t| t.norm.as_str()).collect();
+            if similarity(&old, &new) >= REREAD_SIMILARITY {
+                continue;
+            }
+        }
+        if inserted.len() >= MIN_INSERT_RUN {
+            inserted_words += inserted.len();
+            runs.push(join(inserted));
+        }
+        if deleted.len() >= MIN_INSERT_RUN {
+            removed.push(join(deleted));
+        }
+    }
+
+    WritingDelta {
+        text: runs.join("\n"),
+        inserted_words,
+        removed: removed.join("\n"),
+        scroll,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Fixtures hold two snapshots separated by a line of `=====`
+    fn fixture(raw: &str) -> (&str, &str) {
+        raw.split_once("\n=====\n").expect("fixture needs a ===== separator")
+    }
+
+    #[test]
+    fn typing_at_end_is_writing() {
+        let (before, after) = fixture(include_str!("../tests/fixtures/writing/typing_append.txt"));
+        let delta = writing_delta(before, after);
+        assert_eq!(delta.scroll, None);
+        assert!(delta.inserted_words >= 15, "got {:?}", delta);
+        assert!(delta.text.contains("rollout plan"));
+        assert!(!delta.text.contains("quarterly"));
+        assert!(delta.removed.is_empty());
+    }
+
+    #[test]
+    fn typing_in_the_middle_is_writing() {
+        let (before, after) = fixture(include_str!("../tests/fixtures/writing/typing_middle.txt"));
+        let delta = writing_delta(before, after);
+        assert_eq!(delta.scroll, None);
+        assert!(delta.text.contains("vendor contract"), "got {:?}", delta);
+        assert!(!delta.text.contains("Agenda"));
+    }
+
+    #[test]
+    fn scrolling_down_is_not_writing() {
+        let (before, after) = fixture(include_str!("../tests/fixtures/writing/scroll_down.txt"));
+        let delta = writing_delta(before, after);
+        assert_eq!(delta.scroll, Some(Scroll::Down));
+        assert_eq!(delta.inserted_words, 0, "got {:?}", delta);
+    }
+
+    #[test]
+    fn scrolling_up_is_not_writing() {
+        let (before, after) = fixture(include_str!("../tests/fixtures/writing/scroll_up.txt"));
+        let delta = writing_delta(before, after);
+        assert_eq!(delta.scroll, Some(Scroll::Up));
+        assert_eq!(delta.inserted_words, 0, "got {:?}", delta);
+    }
+
+    #[test]
+    fn reflowed_paragraph_is_not_writing() {
+        let (before, after) = fixture(include_str!("../tests/fixtures/writing/reflow.txt"));
+        let delta = writing_delta(before, after);
+        assert_eq!(delta.inserted_words, 0, "got {:?}", delta);
+    }
+
+    #[test]
+    fn ocr_jitter_is_not_writing() {
+        let (before, after) = fixture(include_str!("../tests/fixtures/writing/ocr_jitter.txt"));
+        let delta = writing_delta(before, after);
+        assert_eq!(delta.inserted_words, 0, "got {:?}", delta);
+    }
+
+    #[test]
+    fn page_change_is_not_writing() {
+        let (before, after) = fixture(include_str!("../tests/fixtures/writing/page_change.txt"));
+        let delta = writing_delta(before, after);
+        assert_eq!(delta.inserted_words, 0, "got {:?}", delta);
+        assert!(delta.text.is_empty());
+    }
+}
@@ -0,0 +1,273 @@
+use serde::{Deserialize, Serialize};
+use tauri_plugin_store::StoreExt;
+
+/// Store file holding the local capture log
+const HISTORY_STORE: &str = "capture_history.json";
+
+/// Oldest entries are dropped once the log grows past this
+const MAX_ENTRIES: usize = 1000;
+
+/// Characters of captured text kept in each entry
+const PREVIEW_CHARS: usize = 160;
+
+/// Serializes read-modify-write of the log (captures are sent from spawned tasks)
+static HISTORY_LOCK: std::sync::Mutex<()> = std::sync::Mutex::new(());
+
+/// What happened to a capture attempt
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum CaptureOutcome {
+    /// Uploaded and accepted by the server
+    Sent,
+    /// Upload attempted but failed (network or API error)
+    Failed,
+    /// Not uploaded because the source's budget was exhausted
+    Throttled,
+    /// Sent, then deleted again by the user
+    Retracted,
+    /// Not uploaded because the secret scanner or a capture rule dropped it
+    Blocked,
+    /// Not uploaded because a near-identical capture was sent recently
+    Duplicate,
+}
+
+/// One entry in the local capture log
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct CaptureRecord {
+    /// Unix timestamp in milliseconds
+    pub timestamp: i64,
+    pub source: String,
+    pub capture_type: String,
+    pub app_name: String,
+    pub preview: String,
+    pub outcome: CaptureOutcome,
+    /// Memory id returned by the server, when sent
+    pub server_id: Option<String>,
+    /// Error message, when failed, throttled, blocked or a duplicate
+    pub error: Option<String>,
+}
+
+impl CaptureRecord {
+    pub fn new(source: &str, meta: Option<&serde_json::Value>, text: &str, outcome: CaptureOutcome) -> Self {
+        let field = |key: &str| {
+            meta.and_then(|m| m[key].as_str())
+                .unwrap_or("")
+                .to_string()
+        };
+        let capture_type = match field("capture_type") {
+            t if t.is_empty() => source.to_string(),
+            t => t,
+        };
+        let mut preview: String = text.chars().take(PREVIEW_CHARS).collect();
+        if text.chars().count() > PREVIEW_CHARS {
+            preview.push_str("...");
+        }
+        Self {
+            timestamp: now_millis(),
+            source: source.to_string(),
+            capture_type,
+            app_name: field("app_name"),
+            preview,
+            outcome,
+            server_id: None,
+            error: None,
+        }
+    }
+}
+
+/// Filters for `get_capture_history` — all optional, combined with AND
+#[derive(Debug, Deserialize, Default)]
+pub struct HistoryFilter {
+    pub source: Option<String>,
+    pub capture_type: Option<String>,
+    /// Case-insensitive substring match on the app name
+    pub app_name: Option<String>,
+    pub outcome: Option<CaptureOutcome>,
+    /// Only entries at or after this unix timestamp (ms)
+    pub since: Option<i64>,
+    /// Only entries at or before this unix timestamp (ms)
+    pub until: Option<i64>,
+    /// Maximum number of entries returned (newest first)
+    pub limit: Option<usize>,
+}
+
+impl HistoryFilter {
+    fn matches(&self, record: &CaptureRecord) -> bool {
+        if let Some(ref source) = self.source {
+            if &record.source != source { return false; }
+        }
+        if let Some(ref capture_type) = self.capture_type {
+            if &record.capture_type != capture_type { return false; }
+        }
+        if let Some(ref app) = self.app_name {
+            if !record.app_name.to_lowercase().contains(&app.to_lowercase()) { return false; }
+        }
+        if let Some(outcome) = self.outcome {
+            if record.outcome != outcome { return false; }
+        }
+        if let Some(since) = self.since {
+            if record.timestamp < since { return false; }
+        }
+        if let Some(until) = self.until {
+            if record.timestamp > until { return false; }
+        }
+        true
+    }
+}
+
+fn now_millis() -> i64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis() as i64
+}
+
+fn load_entries(app: &tauri::AppHandle) -> Vec<CaptureRecord> {
+    app.store(HISTORY_STORE)
+        .ok()
+        .and_then(|s| s.get("entries"))
+        .and_then(|v| serde_json::from_value(v).ok())
+        .unwrap_or_default()
+}
+
+/// Append to `entries`, dropping the oldest past `MAX_ENTRIES`
+fn push_bounded(entries: &mut Vec<CaptureRecord>, entry: CaptureRecord) {
+    entries.push(entry);
+    if entries.len() > MAX_ENTRIES {
+        let excess = entries.len() - MAX_ENTRIES;
+        entries.drain(..excess);
+    }
+}
+
+/// Mark entries with this server id as retracted; true if any was
+fn retract_entries(entries: &mut [CaptureRecord], server_id: &str) -> bool {
+    let mut changed = false;
+    for entry in entries.iter_mut() {
+        if entry.server_id.as_deref() == Some(server_id) {
+            entry.outcome = CaptureOutcome::Retracted;
+            changed = true;
+        }
+    }
+    changed
+}
+
+/// Entries matching `filter`, newest first
+fn select(entries: Vec<CaptureRecord>, filter: &HistoryFilter) -> Vec<CaptureRecord> {
+    let limit = filter.limit.unwrap_or(entries.len());
+    entries
+        .into_iter()
+        .rev()
+        .filter(|r| filter.matches(r))
+        .take(limit)
+        .collect()
+}
+
+/// Append a record to the log, dropping the oldest entries past the bound
+pub fn record(app: &tauri::AppHandle, entry: CaptureRecord) {
+    let _guard = HISTORY_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let store = match app.store(HISTORY_STORE) {
+        Ok(s) => s,
+        Err(_) => return,
+    };
+    let mut entries = load_entries(app);
+    push_bounded(&mut entries, entry);
+    store.set("entries", serde_json::json!(entries));
+    let _ = store.save();
+}
+
+/// Mark the entry with this server id as retracted
+pub fn mark_retracted(app: &tauri::AppHandle, server_id: &str) {
+    let _guard = HISTORY_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let store = match app.store(HISTORY_STORE) {
+        Ok(s) => s,
+        Err(_) => return,
+    };
+    let mut entries = load_entries(app);
+    if retract_entries(&mut entries, server_id) {
+        store.set("entries", serde_json::json!(entries));
+        let _ = store.save();
+    }
+}
+
+/// Query the log, newest entries first
+pub fn query(app: &tauri::AppHandle, filter: &HistoryFilter) -> Vec<CaptureRecord> {
+    select(load_entries(app), filter)
+}
+
+/// Remove every entry from the log
+pub fn clear(app: &tauri::AppHandle) -> Result<(), String> {
+    let _guard = HISTORY_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let store = app.store(HISTORY_STORE).map_err(|e| e.to_string())?;
+    store.set("entries", serde_json::json!([]));
+    store.save().map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 2024-01-15 10:00:00 UTC, in milliseconds
+    const MORNING: i64 = 1_705_312_800_000;
+
+    fn entry(source: &str, app_name: &str, outcome: CaptureOutcome, timestamp: i64) -> CaptureRecord {
+        let meta = serde_json::json!({ "app_name": app_name });
+        CaptureRecord { timestamp, ..CaptureRecord::new(source, Some(&meta), "text", outcome) }
+    }
+
+    #[test]
+    fn keeps_only_the_newest_entries() {
+        let mut entries = Vec::new();
+        for i in 0..MAX_ENTRIES as i64 + 5 {
+            push_bounded(&mut entries, entry("screen", "Notes", CaptureOutcome::Sent, MORNING + i));
+        }
+        assert_eq!(entries.len(), MAX_ENTRIES);
+        assert_eq!(entries[0].timestamp, MORNING + 5);
+        assert_eq!(entries[MAX_ENTRIES - 1].timestamp, MORNING + MAX_ENTRIES as i64 + 4);
+    }
+
+    #[test]
+    fn filters_combine_and_list_newest_first() {
+        let mut clip = entry("clipboard", "Slack", CaptureOutcome::Sent, MORNING + 2000);
+        clip.capture_type = "clipboard_image".to_string();
+        let entries = vec![
+            entry("screen", "Google Chrome", CaptureOutcome::Sent, MORNING),
+            entry("screen", "Notes", CaptureOutcome::Duplicate, MORNING + 1000),
+            clip,
+            entry("screen", "Google Chrome", CaptureOutcome::Failed, MORNING + 3000),
+        ];
+        let times = |filter: HistoryFilter| -> Vec<i64> {
+            select(entries.clone(), &filter).iter().map(|r| r.timestamp - MORNING).collect()
+        };
+
+        assert_eq!(times(HistoryFilter::default()), vec![3000, 2000, 1000, 0]);
+        assert_eq!(times(HistoryFilter { source: Some("clipboard".to_string()), ..Default::default() }), vec![2000]);
+        assert_eq!(times(HistoryFilter { capture_type: Some("screen".to_string()), ..Default::default() }), vec![3000, 1000, 0]);
+        assert_eq!(times(HistoryFilter { app_name: Some("chrome".to_string()), ..Default::default() }), vec![3000, 0]);
+        assert_eq!(times(HistoryFilter { outcome: Some(CaptureOutcome::Duplicate), ..Default::default() }), vec![1000]);
+        assert_eq!(times(HistoryFilter { since: Some(MORNING + 1000), ..Default::default() }), vec![3000, 2000, 1000]);
+        assert_eq!(times(HistoryFilter { until: Some(MORNING + 1000), ..Default::default() }), vec![1000, 0]);
+        assert_eq!(times(HistoryFilter { limit: Some(2), ..Default::default() }), vec![3000, 2000]);
+        assert_eq!(
+            times(HistoryFilter {
+                app_name: Some("Google".to_string()),
+                outcome: Some(CaptureOutcome::Sent),
+                ..Default::default()
+            }),
+            vec![0]
+        );
+    }
+
+    #[test]
+    fn retracting_marks_only_the_matching_entry() {
+        let mut sent = entry("selection", "Notes", CaptureOutcome::Sent, MORNING);
+        sent.server_id = Some("mem_1".to_string());
+        let mut other = entry("selection", "Notes", CaptureOutcome::Sent, MORNING + 1);
+        other.server_id = Some("mem_2".to_string());
+        let mut entries = vec![sent, other];
+
+        assert!(retract_entries(&mut entries, "mem_1"));
+        assert_eq!(entries[0].outcome, CaptureOutcome::Retracted);
+        assert_eq!(entries[1].outcome, CaptureOutcome::Sent);
+        assert!(!retract_entries(&mut entries, "mem_404"));
+    }
+}
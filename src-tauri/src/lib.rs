@@ -9,8 +9,25 @@ use tauri_plugin_global_shortcut::{Code, GlobalShortcutExt, Shortcut};
 use tauri_plugin_notification::NotificationExt;
 use tauri_plugin_store::StoreExt;
 
+mod accessibility;
 mod api;
+mod budget;
+mod capture;
+mod change_tracker;
+mod clipboard;
+mod dedup;
+mod diff;
+mod history;
+mod ocr;
+mod pii;
 mod platform;
+mod privacy;
+mod rules;
+mod screen_change;
+mod secrets;
+mod similarity;
+mod undo;
+mod writing;
 
 /// Flag to distinguish intentional quit from window-close
 static SHOULD_QUIT: std::sync::atomic::AtomicBool = std::sync::atomic::AtomicBool::new(false);
@@ -18,6 +35,10 @@ static SHOULD_QUIT: std::sync::atomic::AtomicBool = std::sync::atomic::AtomicBoo
 /// Ambient snooze: unix timestamp (seconds) until which ambient popups are suppressed
 static SNOOZE_UNTIL: std::sync::atomic::AtomicI64 = std::sync::atomic::AtomicI64::new(0);
 
+/// Writing in progress, shared with the exit handler so it isn't lost on quit
+static WRITING_TRACKER: std::sync::Mutex<writing::WritingTracker> =
+    std::sync::Mutex::new(writing::WritingTracker::new());
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct AppConfig {
     pub api_url: String,
@@ -67,12 +88,17 @@ pub async fn save_service_text(app_handle: tauri::AppHandle, text: String) {
         text.clone()
     };
 
-    match api::capture(&url, &token, &text, "selection", Some(meta)).await {
-        Ok(_) => {
+    match capture::submit(&app_handle, &url, &token, &text, "selection", Some(meta)).await {
+        Ok(id) => {
+            let body = if undo::offer(&app_handle, &id) {
+                format!("{}\nUndo from the tray menu.", preview)
+            } else {
+                preview
+            };
             let _ = app_handle.notification()
                 .builder()
                 .title("Saved to Reattend")
-                .body(&preview)
+                .body(&body)
                 .show();
         }
         Err(e) => {
@@ -131,7 +157,7 @@ async fn capture_text(app: tauri::AppHandle, text: String, source: String) -> Re
         .and_then(|v| v.as_str().map(|s| s.to_string()))
         .unwrap_or_default();
 
-    api::capture(&url, &token, &text, &source, None).await
+    capture::submit(&app, &url, &token, &text, &source, None).await
 }
 
 #[tauri::command]
@@ -186,7 +212,7 @@ async fn analyze_screen(
         .and_then(|v| v.as_str().map(|s| s.to_string()))
         .unwrap_or_default();
 
-    api::analyze(&url, &token, &screen_text, &app_name).await
+    capture::analyze(&app, &url, &token, &screen_text, &app_name).await
 }
 
 /// Snooze ambient popups for N minutes
@@ -200,18 +226,191 @@ async fn snooze_ambient(minutes: u64) -> Result<(), String> {
     Ok(())
 }
 
-/// Run OCR capture using the platform-specific implementation
+/// Current capture budgets and today's counters for each passive source
+#[tauri::command]
+async fn get_capture_budgets(app: tauri::AppHandle) -> Result<Vec<budget::BudgetStatus>, String> {
+    Ok(budget::status(&app))
+}
+
+/// Update per-source capture budgets (0 = unlimited)
+#[tauri::command]
+async fn save_capture_budgets(
+    app: tauri::AppHandle,
+    budgets: std::collections::HashMap<String, budget::BudgetLimit>,
+) -> Result<(), String> {
+    budget::save_limits(&app, budgets)
+}
+
+/// Secret-scanner rules with their current drop/redact action
+#[tauri::command]
+async fn get_secret_rules(app: tauri::AppHandle) -> Result<Vec<serde_json::Value>, String> {
+    let actions = secrets::load_actions(&app);
+    Ok(secrets::SECRET_RULES
+        .iter()
+        .map(|(id, description, _)| serde_json::json!({
+            "rule": id,
+            "description": description,
+            "action": actions.get(*id),
+        }))
+        .collect())
+}
+
+/// Update the drop/redact action of secret-scanner rules
+#[tauri::command]
+async fn save_secret_rules(
+    app: tauri::AppHandle,
+    rules: std::collections::HashMap<String, secrets::SecretAction>,
+) -> Result<(), String> {
+    secrets::save_actions(&app, rules)
+}
+
+/// PII entity types with their current keep/mask/hash mode
+#[tauri::command]
+async fn get_pii_redaction(app: tauri::AppHandle) -> Result<Vec<serde_json::Value>, String> {
+    let modes = pii::load_modes(&app);
+    Ok(pii::PII_ENTITIES
+        .iter()
+        .map(|(id, description, _)| serde_json::json!({
+            "entity": id,
+            "description": description,
+            "mode": modes.get(*id),
+        }))
+        .collect())
+}
+
+/// Update the keep/mask/hash mode of PII entity types
+#[tauri::command]
+async fn save_pii_redaction(
+    app: tauri::AppHandle,
+    modes: std::collections::HashMap<String, pii::PiiMode>,
+) -> Result<(), String> {
+    pii::save_modes(&app, modes)
+}
+
+/// Window-title and browser-domain allow/deny rules
+#[tauri::command]
+async fn get_capture_rules(app: tauri::AppHandle) -> Result<rules::CaptureRules, String> {
+    Ok(rules::load(&app))
+}
+
+/// What the saved capture rules can enforce on this platform, and why
+/// browser windows may be skipped
+#[tauri::command]
+async fn get_capture_rules_status(app: tauri::AppHandle) -> Result<rules::RulesStatus, String> {
+    Ok(rules::status(&rules::load(&app)))
+}
+
+/// Replace the window-title and browser-domain capture rules
+#[tauri::command]
+async fn save_capture_rules(app: tauri::AppHandle, rules: rules::CaptureRules) -> Result<(), String> {
+    rules::save(&app, rules)
+}
+
+/// Cross-stream near-duplicate settings
+#[tauri::command]
+async fn get_dedup_settings(app: tauri::AppHandle) -> Result<dedup::DedupSettings, String> {
+    Ok(dedup::load_settings(&app))
+}
+
+/// Update the near-duplicate time window and distance threshold
+#[tauri::command]
+async fn save_dedup_settings(app: tauri::AppHandle, settings: dedup::DedupSettings) -> Result<(), String> {
+    dedup::save_settings(&app, settings)
+}
+
+/// OCR provider order, languages and capture area
+#[tauri::command]
+async fn get_ocr_settings(app: tauri::AppHandle) -> Result<ocr::OcrSettings, String> {
+    Ok(ocr::load_settings(&app))
+}
+
+/// Update the OCR provider order; rejects providers not built into this binary
+#[tauri::command]
+async fn save_ocr_settings(app: tauri::AppHandle, settings: ocr::OcrSettings) -> Result<(), String> {
+    ocr::save_settings(&app, settings)
+}
+
+/// OCR providers built into this binary, local ones first
+#[tauri::command]
+async fn get_ocr_providers() -> Result<Vec<&'static str>, String> {
+    Ok(ocr::available())
+}
+
+/// Size and OCR confidence of recent server OCR uploads, for tuning the upload settings
+#[tauri::command]
+async fn get_ocr_upload_stats() -> Result<ocr::upload::UploadStats, String> {
+    Ok(ocr::upload::stats())
+}
+
+/// Connected displays, for choosing what screen OCR captures
+#[tauri::command]
+async fn get_displays(app: tauri::AppHandle) -> Result<Vec<platform::DisplayInfo>, String> {
+    platform::platform_list_displays(&app).await
+}
+
+/// Thresholds for skipping OCR of unchanged screens
+#[tauri::command]
+async fn get_screen_change_settings(app: tauri::AppHandle) -> Result<screen_change::ChangeSettings, String> {
+    Ok(screen_change::load_settings(&app))
+}
+
+#[tauri::command]
+async fn save_screen_change_settings(
+    app: tauri::AppHandle,
+    settings: screen_change::ChangeSettings,
+) -> Result<(), String> {
+    screen_change::save_settings(&app, settings)
+}
+
+/// How often OCR was skipped and how much screens changed, for tuning the thresholds
+#[tauri::command]
+async fn get_screen_change_stats() -> Result<screen_change::ChangeStats, String> {
+    Ok(screen_change::stats())
+}
+
+/// Local log of capture attempts, newest first, so users can audit uploads
+#[tauri::command]
+async fn get_capture_history(
+    app: tauri::AppHandle,
+    filter: Option<history::HistoryFilter>,
+) -> Result<Vec<history::CaptureRecord>, String> {
+    Ok(history::query(&app, &filter.unwrap_or_default()))
+}
+
+/// Delete a sent capture (e.g. a passive capture picked from the history)
+#[tauri::command]
+async fn retract_capture(app: tauri::AppHandle, server_id: String) -> Result<(), String> {
+    let store = app
+        .store("config.json")
+        .map_err(|e| e.to_string())?;
+    let url = store
+        .get("api_url")
+        .and_then(|v| v.as_str().map(|s| s.to_string()))
+        .unwrap_or_else(|| "https://reattend.com".to_string());
+    let token = store
+        .get("api_token")
+        .and_then(|v| v.as_str().map(|s| s.to_string()))
+        .unwrap_or_default();
+
+    capture::retract(&app, &url, &token, &server_id).await
+}
+
+/// Wipe the local capture log
+#[tauri::command]
+async fn clear_capture_history(app: tauri::AppHandle) -> Result<(), String> {
+    history::clear(&app)
+}
+
+/// Run OCR capture with the configured provider chain
 #[tauri::command]
-async fn run_ocr_capture(app: tauri::AppHandle) -> Result<serde_json::Value, String> {
-    platform::platform_capture_screen_ocr(&app).await
+async fn run_ocr_capture(app: tauri::AppHandle) -> Result<ocr::OcrResult, String> {
+    ocr::recognize_screen_now(&app).await
 }
 
 // ── Productive apps: OCR more frequently (every 20s instead of 60s) ────────
-// Browsers, docs, email, chat, PM tools — where writing and reading happen
+// Browsers (see `platform::browser`), docs, email, chat, PM tools — where
+// writing and reading happen
 const PRODUCTIVE_APPS: &[&str] = &[
-    // Browsers — Gmail, Google Docs, Linear, etc. all run here
-    "google chrome", "chrome", "safari", "firefox", "arc", "brave",
-    "microsoft edge", "edge", "opera", "vivaldi",
     // Email clients
     "mail", "outlook", "spark", "airmail", "thunderbird",
     // Docs & notes
@@ -227,6 +426,10 @@ const PRODUCTIVE_APPS: &[&str] = &[
 
 /// Check if app is a productive app (deserves faster OCR)
 fn is_productive_app(app_name: &str) -> bool {
+    // Browsers — Gmail, Google Docs, Linear, etc. all run here
+    if platform::browser(app_name).is_some() {
+        return true;
+    }
     let lower = app_name.to_lowercase();
     PRODUCTIVE_APPS.iter().any(|app| lower.contains(app))
 }
@@ -263,7 +466,7 @@ const SKIP_APPS: &[&str] = &[
     "reattend",
 ];
 
-/// Check if app should be skipped for screen capture
+/// Check if app should be skipped for screen and clipboard capture
 fn should_skip_app(app_name: &str) -> bool {
     let lower = app_name.to_lowercase();
     SKIP_APPS.iter().any(|skip| lower.contains(skip))
@@ -314,21 +517,6 @@ fn line_shape(line: &str) -> Vec<u8> {
         .collect()
 }
 
-/// Extract text that is NEW compared to previous text (for writing detection)
-fn extract_delta_text(previous: &str, current: &str) -> String {
-    let prev_lines: std::collections::HashSet<&str> = previous.lines()
-        .map(|l| l.trim())
-        .filter(|l| l.len() >= 5)
-        .collect();
-
-    let new_lines: Vec<&str> = current.lines()
-        .map(|l| l.trim())
-        .filter(|l| l.len() >= 5 && !prev_lines.contains(l))
-        .collect();
-
-    new_lines.join("\n")
-}
-
 /// Clean raw OCR text — strip UI noise, keep substantive content.
 fn clean_ocr_text(raw: &str) -> String {
     let mut cleaned_lines: Vec<&str> = Vec::new();
@@ -455,19 +643,27 @@ fn clean_ocr_text(raw: &str) -> String {
     result
 }
 
-/// Save the current text selection to Reattend.
-/// Simulates Cmd/Ctrl+C, reads clipboard, sends to capture API, shows notification.
-async fn save_selection(app_handle: tauri::AppHandle) {
+/// Copy the current selection and read it back from the clipboard.
+/// None unless at least two words are selected.
+async fn read_selection(platform: &dyn platform::Platform) -> Option<String> {
     // Step 1: Simulate copy keystroke
-    platform::platform_simulate_copy();
+    platform.simulate_copy();
 
     // Step 2: Wait for clipboard to update
     tokio::time::sleep(tokio::time::Duration::from_millis(150)).await;
 
     // Step 3: Read clipboard
-    let clip_text = match platform::platform_read_clipboard() {
-        Some(t) if t.split_whitespace().count() >= 2 => t,
-        _ => {
+    platform
+        .read_clipboard()
+        .filter(|t| t.split_whitespace().count() >= 2)
+}
+
+/// Save the current text selection to Reattend.
+/// Simulates Cmd/Ctrl+C, reads clipboard, sends to capture API, shows notification.
+async fn save_selection(app_handle: tauri::AppHandle) {
+    let clip_text = match read_selection(&*platform::get(&app_handle)).await {
+        Some(t) => t,
+        None => {
             let _ = app_handle.notification()
                 .builder()
                 .title("Reattend")
@@ -511,12 +707,17 @@ async fn save_selection(app_handle: tauri::AppHandle) {
         clip_text.clone()
     };
 
-    match api::capture(&url, &token, &clip_text, "selection", Some(meta)).await {
-        Ok(_) => {
+    match capture::submit(&app_handle, &url, &token, &clip_text, "selection", Some(meta)).await {
+        Ok(id) => {
+            let body = if undo::offer(&app_handle, &id) {
+                format!("{}\nUndo from the tray menu.", preview)
+            } else {
+                preview
+            };
             let _ = app_handle.notification()
                 .builder()
                 .title("Saved to Reattend")
-                .body(&preview)
+                .body(&body)
                 .show();
         }
         Err(e) => {
@@ -529,19 +730,334 @@ async fn save_selection(app_handle: tauri::AppHandle) {
     }
 }
 
+/// Describe the window a capture came from: title, app, and the page URL for
+/// browsers. Capture rules are evaluated against this.
+async fn source_window_meta(
+    platform: &dyn platform::Platform,
+    window: &platform::ActiveWindowInfo,
+) -> serde_json::Value {
+    let mut source_window = serde_json::json!({
+        "title": &window.title,
+        "app_name": &window.app_name,
+        "app_id": &window.app_id,
+        "pid": window.pid,
+    });
+    if let Some(page_url) = platform.browser_url(window).await {
+        source_window["url"] = serde_json::json!(page_url);
+    }
+    source_window
+}
+
+/// Text to capture for one clipboard copy: Markdown for text and rich text,
+/// OCR for images (image size and OCR confidence go into `meta`; the PNG is
+/// attached when `attach_images`). None if there is too little to capture.
+async fn clipboard_capture_text(
+    platform: &dyn platform::Platform,
+    content: clipboard::ClipboardContent,
+    meta: &mut serde_json::Value,
+    attach_images: bool,
+) -> Option<(String, Vec<api::Attachment>)> {
+    let mut attachments = Vec::new();
+
+    let text = match content.to_markdown() {
+        Some(text) => text,
+        None => {
+            let image = match content {
+                clipboard::ClipboardContent::Image(image) => image,
+                _ => return None,
+            };
+            let ocr_result = platform.ocr_image(&image).await.ok()?;
+            meta["image_width"] = serde_json::json!(image.width);
+            meta["image_height"] = serde_json::json!(image.height);
+            if let Some(confidence) = ocr_result.confidence {
+                meta["ocr_confidence"] = serde_json::json!(confidence);
+            }
+
+            if attach_images {
+                if let Ok(png) = image.to_png() {
+                    attachments.push(api::Attachment {
+                        filename: "clipboard.png".to_string(),
+                        mime_type: "image/png".to_string(),
+                        data: png,
+                    });
+                }
+            }
+
+            clean_ocr_text(&ocr_result.text)
+        }
+    };
+
+    // Only capture meaningful clipboard content
+    if text.split_whitespace().count() < 5 || text.len() < 30 {
+        return None;
+    }
+    Some((text, attachments))
+}
+
+/// Capture one clipboard change. Rich text is converted to Markdown; images go
+/// through OCR and are optionally attached (config.json → "clipboard_image_attachments").
+/// The copy is attributed to the window that was focused when it happened;
+/// copies from apps in `SKIP_APPS` are ignored.
+async fn capture_clipboard_content(
+    app_handle: &tauri::AppHandle,
+    url: &str,
+    token: &str,
+    event: clipboard::ClipboardEvent,
+) {
+    let clipboard::ClipboardEvent { timestamp, content, window } = event;
+    if let Some(ref window) = window {
+        if privacy::should_skip_window(app_handle, window).await {
+            return;
+        }
+    }
+    let platform = platform::get(app_handle);
+    let app_name = window
+        .as_ref()
+        .map(|w| w.app_name.clone())
+        .filter(|n| !n.is_empty())
+        .unwrap_or_else(|| "Unknown".to_string());
+    // Copies from noise and sensitive apps (terminals, password managers)
+    // are dropped before any OCR or window lookup, as for screen capture
+    if should_skip_app(&app_name) {
+        return;
+    }
+
+    let mut meta = serde_json::json!({
+        "capture_type": content.capture_type(),
+        "app_name": &app_name,
+        "copied_at": timestamp,
+    });
+    if let Some(ref window) = window {
+        meta["source_window"] = source_window_meta(&*platform, window).await;
+    }
+    // Blocked windows must not reach OCR, which may upload the image
+    if rules::check(&rules::load(app_handle), Some(&meta)).is_err() {
+        return;
+    }
+
+    let attach_images = app_handle
+        .store("config.json")
+        .ok()
+        .and_then(|s| s.get("clipboard_image_attachments"))
+        .and_then(|v| v.as_bool())
+        .unwrap_or(false);
+    let (text, attachments) =
+        match clipboard_capture_text(&*platform, content, &mut meta, attach_images).await {
+            Some(captured) => captured,
+            None => return,
+        };
+    let _ = capture::submit_with_attachments(
+        app_handle, url, token, &text, "clipboard", Some(meta), attachments,
+    ).await;
+}
+
+/// Upload a finished writing session as one `writing` capture
+fn submit_writing_session(
+    app_handle: &tauri::AppHandle,
+    url: &str,
+    token: &str,
+    session: writing::FinishedSession,
+) {
+    let (text, meta) = writing_capture(session);
+    let handle = app_handle.clone();
+    let url = url.to_string();
+    let token = token.to_string();
+    tokio::spawn(async move {
+        let _ = capture::submit(&handle, &url, &token, &text, "writing", Some(meta)).await;
+    });
+}
+
+/// Text and metadata of a `writing` capture
+fn writing_capture(session: writing::FinishedSession) -> (String, serde_json::Value) {
+    let mut meta = serde_json::json!({
+        "capture_type": "writing",
+        "app_name": &session.app_name,
+        "session_started_at": session.started_at,
+        "session_ended_at": session.ended_at,
+        "duration_secs": session.duration_secs(),
+        "fragments": session.fragments,
+    });
+    if session.scrolled {
+        meta["scrolled"] = serde_json::json!(true);
+    }
+    if let Some(source_window) = session.source_window {
+        meta["source_window"] = source_window;
+    }
+    let text = if session.text.len() > 4000 {
+        session.text.chars().take(4000).collect::<String>()
+    } else {
+        session.text
+    };
+    (text, meta)
+}
+
+/// Upload the writing session in progress when the app quits, waiting at
+/// most a few seconds for the server
+fn flush_writing_session(app_handle: &tauri::AppHandle) {
+    let session = match WRITING_TRACKER.lock().unwrap_or_else(|e| e.into_inner()).finish() {
+        Some(session) => session,
+        None => return,
+    };
+    let store = match app_handle.store("config.json") {
+        Ok(s) => s,
+        Err(_) => return,
+    };
+    let token = store
+        .get("api_token")
+        .and_then(|v| v.as_str().map(|s| s.to_string()))
+        .unwrap_or_default();
+    if token.is_empty() {
+        return;
+    }
+    let url = store
+        .get("api_url")
+        .and_then(|v| v.as_str().map(|s| s.to_string()))
+        .unwrap_or_else(|| "https://reattend.com".to_string());
+    let (text, meta) = writing_capture(session);
+    tauri::async_runtime::block_on(async {
+        let submit = capture::submit(app_handle, &url, &token, &text, "writing", Some(meta));
+        let _ = tokio::time::timeout(std::time::Duration::from_secs(5), submit).await;
+    });
+}
+
+/// Event-driven clipboard capture: every distinct copy reported by the platform
+/// watcher goes through the capture pipeline, stamped with its copy time.
+async fn clipboard_capture_loop(app_handle: tauri::AppHandle) {
+    let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+    clipboard::spawn_watcher(platform::get(&app_handle), tx);
+
+    while let Some(event) = rx.recv().await {
+        let store = match app_handle.store("config.json") {
+            Ok(s) => s,
+            Err(_) => continue,
+        };
+        let token = store
+            .get("api_token")
+            .and_then(|v| v.as_str().map(|s| s.to_string()))
+            .unwrap_or_default();
+        let url = store
+            .get("api_url")
+            .and_then(|v| v.as_str().map(|s| s.to_string()))
+            .unwrap_or_else(|| "https://reattend.com".to_string());
+
+        if token.is_empty() {
+            continue;
+        }
+
+        capture_clipboard_content(&app_handle, &url, &token, event).await;
+    }
+}
+
+/// Text of the focused window, from its accessibility tree or OCR
+struct ScreenRead {
+    app_name: String,
+    text: String,
+    window: Option<platform::ActiveWindowInfo>,
+    /// "accessibility" or "ocr" (capture metadata → "text_source")
+    source: &'static str,
+    /// Element with keyboard focus (accessibility only)
+    focused: Option<accessibility::FocusedElement>,
+    /// Screen area that was read (OCR only)
+    geometry: Option<ocr::CaptureGeometry>,
+    /// Title and page URL of `window`, so capture rules can match it
+    source_window: Option<serde_json::Value>,
+}
+
+/// Read the focused window's text. The accessibility tree gives the document
+/// text without UI chrome, so OCR is only used when the app exposes too little.
+/// None when there is nothing to capture: one of our windows open, a private
+/// browser window (when `skip_private`), a window the capture `rules` block,
+/// a noise app, a failed OCR or too little text.
+async fn read_screen(
+    platform: &dyn platform::Platform,
+    skip_private: bool,
+    rules: &rules::CaptureRules,
+) -> Option<ScreenRead> {
+    // The user is working in one of our windows; the ambient popup alone is
+    // masked out of the screenshot instead
+    if platform.own_windows().iter().any(|w| !w.is_overlay()) {
+        return None;
+    }
+
+    let mut checked = None;
+    if let Some(window) = platform.active_window() {
+        // Never read a private/incognito browser window
+        if skip_private && privacy::is_private_window(platform, &window).await {
+            return None;
+        }
+        // Rules are checked before reading: OCR may upload a screenshot
+        let meta = serde_json::json!({ "source_window": source_window_meta(platform, &window).await });
+        if rules::check(rules, Some(&meta)).is_err() {
+            return None;
+        }
+        checked = Some((window, meta["source_window"].clone()));
+    }
+
+    let (app_name, text, source, focused, geometry) = match platform.window_text().await.filter(|t| t.is_usable()) {
+        Some(window_text) => {
+            (platform.active_app_name(), window_text.text, "accessibility", window_text.focused, None)
+        }
+        None => {
+            // Run OCR via the configured providers
+            // Nothing to read when the screen hasn't changed since its last OCR
+            let ocr_result = platform.capture_screen_ocr().await.ok()??;
+            // Clean OCR text: strip UI chrome, URLs, tabs, menus → keep content
+            let text = clean_ocr_text(&ocr_result.text);
+            (ocr_result.app_name, text, "ocr", None, ocr_result.geometry)
+        }
+    };
+    let app_name = if app_name.is_empty() { "Unknown".to_string() } else { app_name };
+
+    // Focus may have moved to a private window while the screen was read
+    let window = platform.active_window();
+    if let Some(ref window) = window {
+        if skip_private && privacy::looks_private(window) {
+            return None;
+        }
+    }
+
+    // Skip noise apps entirely
+    if should_skip_app(&app_name) {
+        return None;
+    }
+
+    // Skip if the text is too short
+    if text.split_whitespace().count() < 12 {
+        return None;
+    }
+
+    // The window checked above is only looked up again if focus moved
+    let source_window = match (&window, checked) {
+        (Some(window), Some((read, source_window)))
+            if window.window_id == read.window_id && window.app_name == read.app_name && window.title == read.title =>
+        {
+            Some(source_window)
+        }
+        (Some(window), _) => Some(source_window_meta(platform, window).await),
+        (None, _) => None,
+    };
+    Some(ScreenRead { app_name, text, window, source, focused, geometry, source_window })
+}
+
 /// Background "Passive Second Brain" loop:
-/// 1. Clipboard monitoring (every ~6s) → capture for triage
-/// 2. App switch detection (every 4s) → triggers early OCR
-/// 3. OCR screen capture (dynamic: 20s for productive apps, 60s otherwise)
-/// 4. Writing detection via text deltas → separate capture stream
-/// 5. Ambient recall on every OCR cycle
+/// 1. App switch detection (every 4s) → triggers early OCR
+/// 2. Screen capture: accessibility text, else OCR (dynamic: 20s for productive apps, 60s otherwise)
+/// 3. Writing detection via text deltas → one capture per writing session
+/// 4. Ambient recall on every OCR cycle
+/// (Clipboard capture runs separately in `clipboard_capture_loop`.)
+///
+/// Private/incognito browser windows are never captured by any signal unless
+/// the user opts in (config.json → "capture_private_windows").
+///
+/// Each source is bounded by its capture budget. Once the screen budget is
+/// exhausted, OCR only runs after an app switch until the budget resets.
 async fn passive_capture_loop(app_handle: tauri::AppHandle) {
-    let mut last_ocr_text = String::new();
-    let mut last_clipboard_text = String::new();
+    let platform = platform::get(&app_handle);
     let mut last_app_name = String::new();
+    let mut app_switch_pending = false;
     let mut ticks: u32 = 0; // Each tick = 2s
-    // Per-app text tracking for writing delta detection
-    let mut per_app_text: std::collections::HashMap<String, String> = std::collections::HashMap::new();
+    // Per-window text and capture state, for writing deltas and change detection
+    let mut change_tracker = change_tracker::ChangeTracker::default();
 
     loop {
         tokio::time::sleep(tokio::time::Duration::from_secs(2)).await;
@@ -565,29 +1081,24 @@ async fn passive_capture_loop(app_handle: tauri::AppHandle) {
             continue;
         }
 
-        // --- Signal 1: Clipboard capture (every ~6s) ---
-        if ticks % 3 == 0 {
-            if let Some(clip_text) = platform::platform_read_clipboard() {
-                if clip_text != last_clipboard_text {
-                    last_clipboard_text = clip_text.clone();
-                    // Only capture meaningful clipboard content
-                    if clip_text.split_whitespace().count() >= 5 && clip_text.len() >= 30 {
-                        let meta = serde_json::json!({
-                            "capture_type": "clipboard",
-                            "app_name": &last_app_name,
-                        });
-                        let _ = api::capture(&url, &token, &clip_text, "clipboard", Some(meta)).await;
-                    }
+        // --- Signal 1: App switch detection (every 4s) ---
+        if ticks % 2 == 0 {
+            // Idle, app switch or another document ends the writing session
+            if let Some(window) = platform.active_window() {
+                let finished = WRITING_TRACKER
+                    .lock()
+                    .unwrap_or_else(|e| e.into_inner())
+                    .observe(&window.app_name, &window.title);
+                if let Some(session) = finished {
+                    submit_writing_session(&app_handle, &url, &token, session);
                 }
             }
-        }
 
-        // --- Signal 2: App switch detection (every 4s) ---
-        if ticks % 2 == 0 {
-            let current_app = platform::platform_get_active_app_name();
+            let current_app = platform.active_app_name();
             if !current_app.is_empty() && current_app != "Unknown" {
                 if current_app != last_app_name && !last_app_name.is_empty() {
                     last_app_name = current_app;
+                    app_switch_pending = true;
                     // Force OCR on next tick — use productive-aware interval
                     let next_ocr = if is_productive_app(&last_app_name) { 9 } else { 29 };
                     ticks = next_ocr;
@@ -597,71 +1108,75 @@ async fn passive_capture_loop(app_handle: tauri::AppHandle) {
             }
         }
 
-        // --- Signal 3: OCR screen capture (dynamic interval) ---
+        // --- Signal 2: OCR screen capture (dynamic interval) ---
         // Productive apps: every 20s (ticks % 10), others: every 60s (ticks % 30)
         let ocr_interval: u32 = if is_productive_app(&last_app_name) { 10 } else { 30 };
         if ticks % ocr_interval == 0 {
-            // Run OCR via platform-specific implementation
-            let ocr_result = match platform::platform_capture_screen_ocr(&app_handle).await {
-                Ok(v) => v,
-                Err(_) => continue,
-            };
-
-            let raw_text = ocr_result["text"].as_str().unwrap_or("").to_string();
-            let app_name = ocr_result["appName"].as_str().unwrap_or("Unknown").to_string();
-
-            let app_switched = app_name != last_app_name && !last_app_name.is_empty();
-            last_app_name = app_name.clone();
-
-            // Skip noise apps entirely
-            if should_skip_app(&app_name) {
+            // Screen budget exhausted → degrade to capturing only on app switch
+            let screen_exhausted = budget::is_exhausted(&app_handle, "screen");
+            let app_switch = std::mem::take(&mut app_switch_pending);
+            if screen_exhausted && !app_switch {
                 continue;
             }
 
-            // Clean OCR text: strip UI chrome, URLs, tabs, menus → keep content
-            let cleaned = clean_ocr_text(&raw_text);
+            let skip_private = privacy::skip_private_windows(&app_handle);
+            let capture_rules = rules::load(&app_handle);
+            let ScreenRead { app_name, text: cleaned, window, source, focused, geometry, source_window } = match read_screen(&*platform, skip_private, &capture_rules).await {
+                Some(screen) => screen,
+                None => continue,
+            };
+            last_app_name = app_name.clone();
 
-            // Skip if cleaned text is too short
-            if cleaned.split_whitespace().count() < 12 {
-                continue;
-            }
+            // Changes are tracked per app and window, so bouncing between
+            // windows compares each one with its own previous state. Displays
+            // taken in rotation each show something else, so they are tracked
+            // separately too.
+            let window_title = window.as_ref().map(|w| w.title.as_str()).unwrap_or("");
+            let window_key = match geometry {
+                Some(ocr::CaptureGeometry { area: ocr::CaptureArea::RotateMonitors, display: Some(ref display), .. }) => {
+                    change_tracker::window_key(&app_name, &format!("{} @ display {}", window_title, display.id))
+                }
+                _ => change_tracker::window_key(&app_name, window_title),
+            };
+            let prev_text = change_tracker.update_text(&window_key, &cleaned);
 
             // --- Writing detection via text deltas ---
-            // Compare with previous OCR for this specific app to find new text
+            // Compare with previous OCR of this window to find new text;
+            // deltas accumulate into a session that is uploaded once it ends
             if is_productive_app(&app_name) {
-                let prev_text = per_app_text.get(&app_name).cloned().unwrap_or_default();
-                if !prev_text.is_empty() {
-                    let delta = extract_delta_text(&prev_text, &cleaned);
-                    let delta_words = delta.split_whitespace().count();
-                    // If there's meaningful new text (15+ words), it's likely user writing
-                    if delta_words >= 15 {
-                        let delta_text = if delta.len() > 2000 {
-                            delta.chars().take(2000).collect::<String>()
-                        } else {
-                            delta.clone()
-                        };
-                        let meta = serde_json::json!({
-                            "capture_type": "writing",
-                            "app_name": &app_name,
-                        });
-                        let url_w = url.clone();
-                        let token_w = token.clone();
-                        tokio::spawn(async move {
-                            let _ = api::capture(&url_w, &token_w, &delta_text, "writing", Some(meta)).await;
-                        });
+                match (&window, prev_text) {
+                    (Some(window), Some(prev_text)) => {
+                        let current = cleaned.clone();
+                        let delta = tokio::task::spawn_blocking(move || diff::writing_delta(&prev_text, &current))
+                            .await
+                            .unwrap_or_default();
+                        let mut writing_tracker = WRITING_TRACKER.lock().unwrap_or_else(|e| e.into_inner());
+                        if !delta.removed.is_empty() {
+                            writing_tracker.remove(&window.app_name, &window.title, &delta.removed);
+                        }
+                        if delta.inserted_words > 0 {
+                            let finished = writing_tracker.add(&window.app_name, &window.title, &delta, source_window.clone());
+                            if let Some(session) = finished {
+                                submit_writing_session(&app_handle, &url, &token, session);
+                            }
+                        }
                     }
+                    _ => {}
                 }
-                per_app_text.insert(app_name.clone(), cleaned.clone());
             }
 
             // Skip if text hasn't changed significantly (for screen capture)
-            let similarity = text_similarity(&last_ocr_text, &cleaned);
-            if similarity > 0.75 && !app_switched {
+            let signature = similarity::MinHash::of(&cleaned);
+            let score = change_tracker.similarity_to_captured(&window_key, &signature);
+            let threshold = store
+                .get("screen_similarity_threshold")
+                .and_then(|v| v.as_f64())
+                .unwrap_or(similarity::DEFAULT_THRESHOLD);
+            if score > threshold {
                 // Even if we skip screen capture, still try ambient recall
                 // (the screen content might match memories even if unchanged)
                 continue;
             }
-            last_ocr_text = cleaned.clone();
 
             // Truncate for API
             let capture_text = if cleaned.len() > 3000 {
@@ -671,28 +1186,46 @@ async fn passive_capture_loop(app_handle: tauri::AppHandle) {
             };
 
             // --- Capture: send cleaned text to triage pipeline ---
+            let blocked_by_rules;
+            let submitted;
             {
-                let meta = serde_json::json!({
+                let mut meta = serde_json::json!({
                     "capture_type": "screen",
                     "app_name": &app_name,
+                    "text_source": source,
                 });
+                if let Some(ref source_window) = source_window {
+                    meta["source_window"] = source_window.clone();
+                }
+                if let Some(ref focused) = focused {
+                    meta["focused_element"] = serde_json::json!(focused);
+                }
+                if let Some(ref geometry) = geometry {
+                    meta["capture_geometry"] = serde_json::json!(geometry);
+                }
+                if app_switch {
+                    meta["trigger"] = serde_json::json!("app_switch");
+                }
+                blocked_by_rules = rules::check(&capture_rules, Some(&meta)).is_err();
                 let url_c = url.clone();
                 let token_c = token.clone();
                 let text_c = capture_text.clone();
-                tokio::spawn(async move {
-                    let _ = api::capture(&url_c, &token_c, &text_c, "screen", Some(meta)).await;
+                let handle_c = app_handle.clone();
+                submitted = tokio::spawn(async move {
+                    capture::deliver(&handle_c, &url_c, &token_c, &text_c, "screen", Some(meta), Vec::new()).await.0
                 });
             }
 
             // --- Ambient recall: Grammarly-like popup ---
-            {
+            // Text from a window blocked by capture rules must not reach the server either
+            if !blocked_by_rules {
                 let now = std::time::SystemTime::now()
                     .duration_since(std::time::UNIX_EPOCH)
                     .unwrap_or_default()
                     .as_secs() as i64;
                 let snoozed_until = SNOOZE_UNTIL.load(std::sync::atomic::Ordering::SeqCst);
                 if now >= snoozed_until {
-                    match api::analyze(&url, &token, &capture_text, &app_name).await {
+                    match capture::analyze(&app_handle, &url, &token, &capture_text, &app_name).await {
                         Ok(result) => {
                             if let Some(related) = result["related"].as_array() {
                                 if !related.is_empty() {
@@ -714,23 +1247,16 @@ async fn passive_capture_loop(app_handle: tauri::AppHandle) {
                     }
                 }
             }
-        }
-    }
-}
 
-/// Simple text similarity (Jaccard on words)
-fn text_similarity(a: &str, b: &str) -> f64 {
-    let words_a: std::collections::HashSet<&str> = a.split_whitespace().collect();
-    let words_b: std::collections::HashSet<&str> = b.split_whitespace().collect();
-    if words_a.is_empty() && words_b.is_empty() {
-        return 1.0;
-    }
-    let intersection = words_a.intersection(&words_b).count();
-    let union = words_a.union(&words_b).count();
-    if union == 0 {
-        return 1.0;
+            // Only text that reached the server, or never will, counts as
+            // captured; after a failed or throttled upload the next tick retries
+            if let Ok(outcome) = submitted.await {
+                if !matches!(outcome, history::CaptureOutcome::Failed | history::CaptureOutcome::Throttled) {
+                    change_tracker.mark_captured(&window_key, signature);
+                }
+            }
+        }
     }
-    intersection as f64 / union as f64
 }
 
 /// Create ambient popup at bottom-right of screen
@@ -745,7 +1271,7 @@ fn create_ambient_popup(app: &tauri::AppHandle, url: &str) {
         let app = app_clone;
 
         // Close existing ambient popup if open
-        if let Some(window) = app.get_webview_window("ambient") {
+        if let Some(window) = app.get_webview_window(platform::AMBIENT_WINDOW) {
             let _ = window.close();
         }
 
@@ -762,7 +1288,7 @@ fn create_ambient_popup(app: &tauri::AppHandle, url: &str) {
 
         platform::platform_activate_app();
 
-        if let Ok(window) = WebviewWindowBuilder::new(&app, "ambient", WebviewUrl::App(url.into()))
+        if let Ok(window) = WebviewWindowBuilder::new(&app, platform::AMBIENT_WINDOW, WebviewUrl::App(url.into()))
             .title("Reattend")
             .inner_size(width, height)
             .position(x, y)
@@ -829,9 +1355,34 @@ pub fn run() {
             analyze_screen,
             run_ocr_capture,
             snooze_ambient,
+            get_capture_budgets,
+            save_capture_budgets,
+            get_capture_history,
+            clear_capture_history,
+            retract_capture,
+            get_secret_rules,
+            save_secret_rules,
+            get_pii_redaction,
+            save_pii_redaction,
+            get_capture_rules,
+            get_capture_rules_status,
+            save_capture_rules,
+            get_dedup_settings,
+            save_dedup_settings,
+            get_ocr_settings,
+            save_ocr_settings,
+            get_ocr_providers,
+            get_ocr_upload_stats,
+            get_displays,
+            get_screen_change_settings,
+            save_screen_change_settings,
+            get_screen_change_stats,
         ])
         .setup(|app| {
             // Platform-specific startup
+            let native: platform::SharedPlatform =
+                std::sync::Arc::new(platform::NativePlatform::new(app.handle().clone()));
+            app.manage(native);
             platform::platform_hide_from_dock();
             platform::platform_store_app_handle(&app.handle());
             platform::platform_register_context_menu();
@@ -845,10 +1396,12 @@ pub fn run() {
                 &format!("Save Selection  {}S", shortcut_prefix),
                 true, None::<&str>
             )?;
+            let undo_item = MenuItem::with_id(app, "undo", "Undo Last Save", false, None::<&str>)?;
+            app.manage(undo::UndoMenuItem(undo_item.clone()));
             let ask = MenuItem::with_id(app, "ask", "Ask AI", true, None::<&str>)?;
             let settings = MenuItem::with_id(app, "settings", "Settings", true, None::<&str>)?;
             let separator = PredefinedMenuItem::separator(app)?;
-            let menu = Menu::with_items(app, &[&capture, &save_sel, &ask, &separator, &settings, &separator, &quit])?;
+            let menu = Menu::with_items(app, &[&capture, &save_sel, &undo_item, &ask, &separator, &settings, &separator, &quit])?;
 
             // Build tray icon (embedded at compile time for reliable loading)
             let icon = Image::from_bytes(include_bytes!("../icons/tray-icon.png"))
@@ -872,6 +1425,12 @@ pub fn run() {
                                 save_selection(handle).await;
                             });
                         }
+                        "undo" => {
+                            let handle = app.clone();
+                            tauri::async_runtime::spawn(async move {
+                                undo::undo_last(handle).await;
+                            });
+                        }
                         "ask" => {
                             create_window(app, "ask", "Ask AI", "/", 480.0, 400.0);
                         }
@@ -922,21 +1481,281 @@ pub fn run() {
                 }
             })?;
 
-            // Start passive capture loop in background
+            // Start passive capture loops in background
             let bg_handle = app_handle.clone();
             tauri::async_runtime::spawn(async move {
                 passive_capture_loop(bg_handle).await;
             });
+            let clip_handle = app_handle.clone();
+            tauri::async_runtime::spawn(async move {
+                clipboard_capture_loop(clip_handle).await;
+            });
 
             Ok(())
         })
         .build(tauri::generate_context!())
         .expect("error while building tauri application")
-        .run(|_app, event| {
-            if let RunEvent::ExitRequested { api, .. } = event {
+        .run(|app, event| match event {
+            RunEvent::ExitRequested { api, .. } => {
                 if !SHOULD_QUIT.load(std::sync::atomic::Ordering::SeqCst) {
                     api.prevent_exit();
                 }
             }
+            RunEvent::Exit => flush_writing_session(app),
+            _ => {}
         });
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use platform::fake::FakePlatform;
+
+    const ARTICLE: &str = "\
+Quarterly planning notes for the platform team and its partners
+The migration to the new billing service starts next month after review
+Each team owns a checklist and reports progress in the weekly sync";
+
+    const ARTICLE_CONTINUED: &str = "\
+Quarterly planning notes for the platform team and its partners
+The migration to the new billing service starts next month after review
+Each team owns a checklist and reports progress in the weekly sync
+We agreed to freeze schema changes until the rollback plan has been tested twice in staging";
+
+    #[tokio::test]
+    async fn selection_is_copied_and_read_back() {
+        let platform = FakePlatform::default();
+        platform.select("remember this sentence");
+        assert_eq!(read_selection(&platform).await.as_deref(), Some("remember this sentence"));
+        assert_eq!(platform.copies(), 1);
+    }
+
+    #[tokio::test]
+    async fn selection_needs_two_words() {
+        let platform = FakePlatform::default();
+        assert_eq!(read_selection(&platform).await, None);
+        platform.select("word");
+        assert_eq!(read_selection(&platform).await, None);
+    }
+
+    #[tokio::test]
+    async fn screen_read_is_cleaned_and_attributed() {
+        let platform = FakePlatform::default();
+        platform.focus("Notion", "Planning");
+        platform.push_screen(&format!("https://notion.so/planning\nShare\n{}", ARTICLE));
+        let screen = read_screen(&platform, true, &Default::default()).await.expect("screen read");
+        assert_eq!(screen.app_name, "Notion");
+        assert_eq!(screen.window.map(|w| w.title).as_deref(), Some("Planning"));
+        assert!(screen.text.starts_with("Quarterly planning notes"));
+        assert!(!screen.text.contains("https://"));
+    }
+
+    #[tokio::test]
+    async fn accessibility_text_is_preferred_over_ocr() {
+        let platform = FakePlatform::default();
+        platform.focus("TextEdit", "Planning.txt");
+        let nodes: Vec<accessibility::Node> = ARTICLE
+            .lines()
+            .enumerate()
+            .map(|(i, line)| accessibility::Node {
+                depth: 1,
+                role: accessibility::Role::Paragraph,
+                name: String::new(),
+                value: line.to_string(),
+                focused: i == 2,
+            })
+            .collect();
+        platform.set_window_text(Some(accessibility::collect(&nodes)));
+        platform.push_screen("Unused OCR text from the screenshot that should never be read here at all");
+
+        let screen = read_screen(&platform, true, &Default::default()).await.expect("screen read");
+        assert_eq!(screen.source, "accessibility");
+        assert_eq!(screen.app_name, "TextEdit");
+        assert_eq!(screen.text, ARTICLE);
+        assert_eq!(screen.focused.map(|f| f.role), Some(accessibility::Role::Paragraph));
+        assert!(screen.geometry.is_none());
+
+        // Too little accessible text (e.g. a canvas app) falls back to OCR
+        platform.set_window_text(Some(accessibility::collect(&nodes[..1])));
+        let screen = read_screen(&platform, true, &Default::default()).await.expect("screen read");
+        assert_eq!(screen.source, "ocr");
+        let geometry = screen.geometry.expect("OCR geometry");
+        assert_eq!(geometry.area, ocr::CaptureArea::ActiveWindow);
+        assert_eq!(geometry.bounds, screen.window.expect("window").bounds());
+    }
+
+    #[tokio::test]
+    async fn private_windows_are_not_read_unless_opted_in() {
+        let platform = FakePlatform::default();
+        platform.focus("Google Chrome", "New Tab - Google Chrome (Incognito)");
+        platform.push_screen(ARTICLE);
+        assert!(read_screen(&platform, true, &Default::default()).await.is_none());
+        assert!(read_screen(&platform, false, &Default::default()).await.is_some());
+
+        // Only the browser itself knows
+        platform.focus("Google Chrome", "Planning - Google Docs");
+        platform.set_private_window(Some(true));
+        platform.push_screen(ARTICLE);
+        assert!(read_screen(&platform, true, &Default::default()).await.is_none());
+
+        // Safari can't say, so none of its windows are read
+        platform.focus("Safari", "Planning - Google Docs");
+        platform.set_private_window(None);
+        platform.push_screen(ARTICLE);
+        assert!(read_screen(&platform, true, &Default::default()).await.is_none());
+        assert!(read_screen(&platform, false, &Default::default()).await.is_some());
+    }
+
+    #[tokio::test]
+    async fn focus_moving_to_a_private_window_during_ocr_drops_the_read() {
+        let platform = FakePlatform::default();
+        platform.focus("Firefox", "Planning");
+        platform.push_screen_then_focus(ARTICLE, "Firefox", "Mozilla Firefox Private Browsing");
+        assert!(read_screen(&platform, true, &Default::default()).await.is_none());
+    }
+
+    #[tokio::test]
+    async fn noise_apps_short_text_and_ocr_errors_are_skipped() {
+        let platform = FakePlatform::default();
+        platform.focus("1Password", "Vault");
+        platform.push_screen(ARTICLE);
+        assert!(read_screen(&platform, true, &Default::default()).await.is_none());
+
+        platform.focus("Notes", "Todo");
+        platform.push_screen("Buy milk and eggs today");
+        assert!(read_screen(&platform, true, &Default::default()).await.is_none());
+
+        platform.push_screen_error("Capture error");
+        assert!(read_screen(&platform, true, &Default::default()).await.is_none());
+    }
+
+    #[tokio::test]
+    async fn nothing_is_read_while_our_windows_are_open() {
+        let platform = FakePlatform::default();
+        platform.focus("Notes", "Planning");
+        platform.show_own_window(platform::AMBIENT_WINDOW);
+        platform.push_screen(ARTICLE);
+        assert!(read_screen(&platform, true, &Default::default()).await.is_some());
+
+        platform.show_own_window("capture");
+        platform.push_screen(ARTICLE);
+        assert!(read_screen(&platform, true, &Default::default()).await.is_none());
+    }
+
+    #[tokio::test]
+    async fn windows_blocked_by_rules_are_not_ocred() {
+        let platform = FakePlatform::default();
+        platform.focus("Google Chrome", "Accounts - Chase");
+        platform.set_browser_url("https://secure.chase.com/web/auth");
+        platform.push_screen(ARTICLE);
+        let rules = rules::CaptureRules { deny_domains: vec!["chase.com".to_string()], ..Default::default() };
+        assert!(read_screen(&platform, true, &rules).await.is_none());
+        let rules = rules::CaptureRules { deny_titles: vec!["accounts -*".to_string()], ..Default::default() };
+        assert!(read_screen(&platform, true, &rules).await.is_none());
+
+        // The scripted screen was never taken
+        assert!(read_screen(&platform, true, &Default::default()).await.is_some());
+    }
+
+    #[tokio::test]
+    async fn unchanged_screens_are_not_read() {
+        let platform = FakePlatform::default();
+        platform.focus("Notes", "Planning");
+        platform.push_screen_unchanged();
+        platform.push_screen(ARTICLE);
+        assert!(read_screen(&platform, true, &Default::default()).await.is_none());
+        assert!(read_screen(&platform, true, &Default::default()).await.is_some());
+    }
+
+    #[tokio::test]
+    async fn screen_reads_look_up_the_source_window_once() {
+        let platform = FakePlatform::default();
+        platform.focus("Google Chrome", "Planning - Google Docs");
+        platform.set_browser_url("https://docs.google.com/document/d/1");
+        platform.push_screen(ARTICLE);
+        let screen = read_screen(&platform, true, &Default::default()).await.expect("screen read");
+        let source_window = screen.source_window.expect("source window");
+        assert_eq!(source_window["url"], "https://docs.google.com/document/d/1");
+        assert_eq!(platform.url_lookups(), 1);
+
+        // Focus moved while the screen was read: the new window is described
+        platform.push_screen_then_focus(ARTICLE, "Google Chrome", "Roadmap - Google Docs");
+        let screen = read_screen(&platform, true, &Default::default()).await.expect("screen read");
+        assert_eq!(screen.source_window.expect("source window")["title"], "Roadmap - Google Docs");
+        assert_eq!(platform.url_lookups(), 3);
+    }
+
+    #[tokio::test]
+    async fn source_window_includes_the_browser_url() {
+        let platform = FakePlatform::default();
+        platform.set_browser_url("https://docs.google.com/document/d/1");
+        let window = platform::fake::window("Google Chrome", "Planning - Google Docs");
+        let meta = source_window_meta(&platform, &window).await;
+        assert_eq!(meta["title"], "Planning - Google Docs");
+        assert_eq!(meta["url"], "https://docs.google.com/document/d/1");
+    }
+
+    #[tokio::test]
+    async fn clipboard_html_is_captured_as_markdown() {
+        let platform = FakePlatform::default();
+        let content = clipboard::ClipboardContent::Html {
+            html: "<p>The <b>launch</b> moves to Thursday because the vendor contract is late</p>".to_string(),
+            text: None,
+        };
+        let mut meta = serde_json::json!({});
+        let (text, attachments) = clipboard_capture_text(&platform, content, &mut meta, true)
+            .await
+            .expect("captured");
+        assert!(text.contains("**launch**"), "{}", text);
+        assert!(attachments.is_empty());
+    }
+
+    #[tokio::test]
+    async fn clipboard_images_go_through_ocr() {
+        let platform = FakePlatform::default();
+        platform.set_image_text("Invoice total due by the end of the month for the design work");
+        let image = clipboard::ClipboardImage { width: 2, height: 1, rgba: vec![255; 8] };
+        let mut meta = serde_json::json!({});
+        let (text, attachments) =
+            clipboard_capture_text(&platform, clipboard::ClipboardContent::Image(image), &mut meta, true)
+                .await
+                .expect("captured");
+        assert!(text.starts_with("Invoice total"));
+        assert_eq!(meta["image_width"], 2);
+        assert_eq!(meta["ocr_confidence"], 0.9);
+        assert_eq!(attachments.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn short_clipboard_text_is_not_captured() {
+        let platform = FakePlatform::default();
+        let content = clipboard::ClipboardContent::Text("ok thanks".to_string());
+        let mut meta = serde_json::json!({});
+        assert!(clipboard_capture_text(&platform, content, &mut meta, false).await.is_none());
+    }
+
+    #[tokio::test]
+    async fn writing_between_screen_reads_becomes_a_session() {
+        let platform = FakePlatform::default();
+        platform.focus("Notion", "Planning");
+        platform.push_screen(ARTICLE);
+        platform.push_screen(ARTICLE_CONTINUED);
+
+        let mut changes = change_tracker::ChangeTracker::default();
+        let mut sessions = writing::WritingTracker::default();
+        for _ in 0..2 {
+            let screen = read_screen(&platform, true, &Default::default()).await.expect("screen read");
+            let window = screen.window.expect("focused window");
+            let key = change_tracker::window_key(&screen.app_name, &window.title);
+            if let Some(previous) = changes.update_text(&key, &screen.text) {
+                let delta = diff::writing_delta(&previous, &screen.text);
+                assert!(sessions.add(&window.app_name, &window.title, &delta, None).is_none());
+            }
+        }
+
+        let session = sessions.finish().expect("writing session");
+        assert_eq!(session.app_name, "Notion");
+        assert!(session.text.contains("freeze schema changes"), "{}", session.text);
+        assert!(!session.text.contains("Quarterly"));
+    }
+}
@@ -97,3 +97,47 @@ void hide_from_dock(void) {
         NSLog(@"[Reattend] hide_from_dock exception: %@", e);
     }
 }
+
+// Read RTF from the general pasteboard (arboard only exposes text, HTML and images).
+// Returns a malloc'd UTF-8 string the caller must release with free_clipboard_string, or NULL.
+char *read_clipboard_rtf(void) {
+    @try {
+        NSData *data = [[NSPasteboard generalPasteboard] dataForType:NSPasteboardTypeRTF];
+        if (!data || data.length == 0) return NULL;
+        NSString *rtf = [[NSString alloc] initWithData:data encoding:NSUTF8StringEncoding];
+        if (!rtf) rtf = [[NSString alloc] initWithData:data encoding:NSWindowsCP1252StringEncoding];
+        if (!rtf) return NULL;
+        return strdup([rtf UTF8String]);
+    } @catch (NSException *e) {
+        NSLog(@"[Reattend] read_clipboard_rtf exception: %@", e);
+        return NULL;
+    }
+}
+
+void free_clipboard_string(char *ptr) {
+    if (ptr) free(ptr);
+}
+
+// Bundle identifier of a running app (e.g. "com.google.Chrome"), or NULL.
+// Caller releases the result with free_clipboard_string.
+char *bundle_id_for_pid(int pid) {
+    @try {
+        NSRunningApplication *app = [NSRunningApplication runningApplicationWithProcessIdentifier:pid];
+        NSString *bundleId = app.bundleIdentifier;
+        if (!bundleId) return NULL;
+        return strdup([bundleId UTF8String]);
+    } @catch (NSException *e) {
+        NSLog(@"[Reattend] bundle_id_for_pid exception: %@", e);
+        return NULL;
+    }
+}
+
+// NSPasteboard change counter — increments on every copy, in any app.
+long pasteboard_change_count(void) {
+    @try {
+        return (long)[[NSPasteboard generalPasteboard] changeCount];
+    } @catch (NSException *e) {
+        NSLog(@"[Reattend] pasteboard_change_count exception: %@", e);
+        return -1;
+    }
+}
@@ -0,0 +1,465 @@
+use serde::{Deserialize, Serialize};
+use crate::platform::{ActiveWindowInfo, DisplayInfo, Rect};
+use crate::screen_change::{self, Fingerprint};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use tauri_plugin_store::StoreExt;
+
+mod server;
+// Screen uploads are Windows/Linux only: on macOS the Swift helper keeps the pixels
+#[cfg_attr(target_os = "macos", allow(dead_code))]
+pub mod upload;
+#[cfg(feature = "tesseract")]
+mod tesseract;
+#[cfg(target_os = "macos")]
+mod vision;
+
+/// OCR output, normalized across providers
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OcrResult {
+    pub text: String,
+    /// App focused when the screen was captured
+    pub app_name: String,
+    /// Mean recognition confidence in 0..=1, if the provider reports one
+    pub confidence: Option<f64>,
+    /// Provider that produced the result
+    pub provider: &'static str,
+    /// Screen area that was read (screen OCR only)
+    pub geometry: Option<CaptureGeometry>,
+}
+
+impl OcrResult {
+    /// Parse the `{"text", "appName", "confidence"}` JSON returned by the Swift
+    /// helper and the server. Percent confidences are scaled to 0..=1.
+    pub fn from_json(value: &serde_json::Value, provider: &'static str) -> Self {
+        Self {
+            text: value["text"].as_str().unwrap_or("").to_string(),
+            app_name: value["appName"].as_str().unwrap_or("").to_string(),
+            confidence: value["confidence"]
+                .as_f64()
+                .map(|c| if c > 1.0 { c / 100.0 } else { c }),
+            provider,
+            geometry: None,
+        }
+    }
+}
+
+/// Which part of the screen screen OCR reads (config.json → "ocr" → "capture_area")
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CaptureArea {
+    /// The focused window only, so text from windows behind it isn't
+    /// attributed to the focused app
+    #[default]
+    ActiveWindow,
+    /// The whole monitor holding the focused window
+    ActiveMonitor,
+    AllMonitors,
+    /// One whole monitor per capture, taking each in turn
+    RotateMonitors,
+}
+
+/// What a screen capture should cover
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScreenRequest {
+    pub area: CaptureArea,
+    /// Bounds of the focused window, if known
+    pub window: Option<Rect>,
+    /// Display to capture; the one holding the focused window (or the
+    /// primary) when None. Unused for `AllMonitors`.
+    pub display: Option<DisplayInfo>,
+    /// Our own windows, blanked out of the screenshot
+    pub exclude: Vec<Rect>,
+}
+
+impl ScreenRequest {
+    /// Picks the display for `area` from `displays` — the one holding the
+    /// focused window, or number `turn` in rotation. Falls back to the active
+    /// monitor when there are no window bounds to crop to.
+    pub fn new(area: CaptureArea, window: Option<&ActiveWindowInfo>, displays: &[DisplayInfo], turn: usize) -> Self {
+        let window = window.map(|w| w.bounds()).filter(|b| !b.is_empty());
+        let area = match (area, window) {
+            (CaptureArea::ActiveWindow, None) => CaptureArea::ActiveMonitor,
+            (area, _) => area,
+        };
+        let display = match area {
+            CaptureArea::AllMonitors => None,
+            CaptureArea::RotateMonitors if !displays.is_empty() => displays.get(turn % displays.len()).cloned(),
+            _ => crate::platform::display_for(displays, window).cloned(),
+        };
+        Self { area, window, display, exclude: Vec::new() }
+    }
+}
+
+/// Screen area an OCR result was read from (capture metadata → "capture_geometry")
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct CaptureGeometry {
+    pub area: CaptureArea,
+    #[serde(flatten)]
+    pub bounds: Rect,
+    /// Display that was captured (all but `AllMonitors`)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub display: Option<DisplayInfo>,
+}
+
+/// A screenshot waiting for OCR. Taken once per tick, so every provider in
+/// the chain reads the same pixels and change detection runs only once.
+pub struct ScreenCapture {
+    /// App focused when the screen was captured
+    pub app_name: String,
+    pub geometry: CaptureGeometry,
+    /// Thumbnail for skipping OCR when nothing changed
+    pub fingerprint: Fingerprint,
+    #[cfg(any(target_os = "windows", target_os = "linux"))]
+    pub image: image::RgbaImage,
+    /// The macOS helper keeps the pixels; this names them
+    #[cfg(target_os = "macos")]
+    pub id: u64,
+}
+
+pub type OcrFuture<'a> = crate::platform::PlatformFuture<'a, Result<OcrResult, String>>;
+
+/// One way of turning pixels into text
+pub trait OcrProvider: Send + Sync {
+    fn name(&self) -> &'static str;
+    /// OCR a screen capture
+    fn screen<'a>(&'a self, capture: &'a ScreenCapture) -> OcrFuture<'a>;
+    /// OCR a PNG-encoded image
+    fn image<'a>(&'a self, png: &'a [u8]) -> OcrFuture<'a>;
+}
+
+/// OCR settings (config.json → "ocr")
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub struct OcrSettings {
+    /// Providers to try, in order; the next one is used when one fails.
+    /// "vision" (macOS), "tesseract" (builds with the `tesseract` feature),
+    /// "server" (uploads the image to the Reattend API). Builds with a local
+    /// provider only use "server" when it is added here.
+    pub providers: Vec<String>,
+    /// Tesseract language(s), e.g. "eng" or "eng+deu"
+    pub tesseract_language: String,
+    /// Apple Vision languages as BCP 47 codes, e.g. ["en-US", "de-DE"];
+    /// empty lets Vision use its defaults
+    pub vision_languages: Vec<String>,
+    /// Part of the screen that screen OCR reads
+    pub capture_area: CaptureArea,
+    /// How screenshots are prepared for the "server" provider
+    pub upload: upload::UploadSettings,
+}
+
+impl Default for OcrSettings {
+    fn default() -> Self {
+        Self {
+            providers: default_providers(),
+            tesseract_language: "eng".to_string(),
+            vision_languages: Vec::new(),
+            capture_area: CaptureArea::default(),
+            upload: upload::UploadSettings::default(),
+        }
+    }
+}
+
+/// Providers built into this binary, local ones first
+pub fn available() -> Vec<&'static str> {
+    let mut providers = Vec::new();
+    #[cfg(target_os = "macos")]
+    providers.push("vision");
+    #[cfg(feature = "tesseract")]
+    providers.push("tesseract");
+    providers.push("server");
+    providers
+}
+
+/// The local providers of this build; "server" only when there are none,
+/// as it is then the only way to OCR. Uploading images is otherwise opt-in,
+/// never a silent fallback for a local provider that failed.
+fn default_providers() -> Vec<String> {
+    let local: Vec<String> = available()
+        .into_iter()
+        .filter(|p| *p != "server")
+        .map(str::to_string)
+        .collect();
+    if local.is_empty() { vec!["server".to_string()] } else { local }
+}
+
+/// Check provider names against this build and drop repeats
+fn validate(settings: OcrSettings) -> Result<OcrSettings, String> {
+    let available = available();
+    let mut providers: Vec<String> = Vec::new();
+    for name in settings.providers {
+        let name = name.trim().to_lowercase();
+        if !available.contains(&name.as_str()) {
+            return Err(format!("OCR provider \"{}\" is not available in this build", name));
+        }
+        if !providers.contains(&name) {
+            providers.push(name);
+        }
+    }
+    if providers.is_empty() {
+        return Err("At least one OCR provider is required".to_string());
+    }
+    settings.upload.validate()?;
+    let language = settings.tesseract_language.trim();
+    Ok(OcrSettings {
+        providers,
+        tesseract_language: if language.is_empty() { "eng".to_string() } else { language.to_string() },
+        vision_languages: settings
+            .vision_languages
+            .iter()
+            .map(|l| l.trim().to_string())
+            .filter(|l| !l.is_empty())
+            .collect(),
+        capture_area: settings.capture_area,
+        upload: settings.upload,
+    })
+}
+
+pub fn load_settings(app: &tauri::AppHandle) -> OcrSettings {
+    app.store("config.json")
+        .ok()
+        .and_then(|s| s.get("ocr"))
+        .and_then(|v| serde_json::from_value(v).ok())
+        .unwrap_or_default()
+}
+
+pub fn save_settings(app: &tauri::AppHandle, settings: OcrSettings) -> Result<(), String> {
+    let settings = validate(settings)?;
+    let store = app.store("config.json").map_err(|e| e.to_string())?;
+    store.set("ocr", serde_json::json!(settings));
+    store.save().map_err(|e| e.to_string())
+}
+
+/// The configured providers, in order. Names not built into this binary
+/// (e.g. a config copied from another machine) are skipped.
+fn providers(app: &tauri::AppHandle, settings: &OcrSettings) -> Vec<Box<dyn OcrProvider>> {
+    settings
+        .providers
+        .iter()
+        .filter_map(|name| -> Option<Box<dyn OcrProvider>> {
+            match name.as_str() {
+                #[cfg(target_os = "macos")]
+                "vision" => Some(Box::new(vision::VisionProvider::new(app.clone(), &settings.vision_languages))),
+                #[cfg(feature = "tesseract")]
+                "tesseract" => Some(Box::new(tesseract::TesseractProvider::new(&settings.tesseract_language))),
+                "server" => Some(Box::new(server::ServerProvider::new(app.clone(), settings.upload.clone()))),
+                _ => None,
+            }
+        })
+        .collect()
+}
+
+/// Run `ocr` on each provider in turn and return the first success,
+/// or every provider's error
+async fn first_success<'a>(
+    providers: &'a [Box<dyn OcrProvider>],
+    ocr: impl Fn(&'a dyn OcrProvider) -> OcrFuture<'a>,
+) -> Result<OcrResult, String> {
+    let mut errors = Vec::new();
+    for provider in providers {
+        match ocr(provider.as_ref()).await {
+            Ok(result) => return Ok(result),
+            Err(e) => errors.push(format!("{}: {}", provider.name(), e)),
+        }
+    }
+    if errors.is_empty() {
+        Err("No OCR provider configured".to_string())
+    } else {
+        Err(errors.join("; "))
+    }
+}
+
+/// Screen captures taken in `RotateMonitors` mode, to pick the next display
+static ROTATION: AtomicUsize = AtomicUsize::new(0);
+
+/// Capture the configured part of the screen around `window`
+async fn capture_screen(
+    app: &tauri::AppHandle,
+    settings: &OcrSettings,
+    window: Option<&ActiveWindowInfo>,
+) -> Result<ScreenCapture, String> {
+    // Without a display list the capture falls back to the primary display
+    let displays = crate::platform::platform_list_displays(app).await.unwrap_or_default();
+    let turn = match settings.capture_area {
+        CaptureArea::RotateMonitors => ROTATION.fetch_add(1, Ordering::Relaxed),
+        _ => 0,
+    };
+    let request = ScreenRequest {
+        exclude: crate::platform::own_windows(app).into_iter().map(|w| w.bounds).collect(),
+        ..ScreenRequest::new(settings.capture_area, window, &displays, turn)
+    };
+    crate::platform::platform_capture_screen(app, &request).await
+}
+
+/// OCR the configured part of the screen with the configured provider
+/// chain. None when it looks the same as at its last OCR (see `screen_change`).
+pub async fn recognize_screen(app: &tauri::AppHandle) -> Result<Option<OcrResult>, String> {
+    let settings = load_settings(app);
+    let window = crate::platform::platform_get_active_window();
+    let capture = capture_screen(app, &settings, window.as_ref()).await?;
+
+    // Each window (and, when rotating, each display) is compared with itself
+    let title = window.as_ref().map_or("", |w| w.title.as_str());
+    let display = capture.geometry.display.as_ref().map_or(0, |d| d.id);
+    let key = format!("{} @ {}", crate::change_tracker::window_key(&capture.app_name, title), display);
+    if !screen_change::should_ocr(app, &key, capture.fingerprint.clone()) {
+        return Ok(None);
+    }
+    let providers = providers(app, &settings);
+    first_success(&providers, |p| p.screen(&capture)).await.map(Some)
+}
+
+/// OCR the configured part of the screen even if it hasn't changed
+pub async fn recognize_screen_now(app: &tauri::AppHandle) -> Result<OcrResult, String> {
+    let settings = load_settings(app);
+    let window = crate::platform::platform_get_active_window();
+    let capture = capture_screen(app, &settings, window.as_ref()).await?;
+    let providers = providers(app, &settings);
+    first_success(&providers, |p| p.screen(&capture)).await
+}
+
+/// OCR a PNG image with the configured provider chain
+pub async fn recognize_image(app: &tauri::AppHandle, png: &[u8]) -> Result<OcrResult, String> {
+    let providers = providers(app, &load_settings(app));
+    first_success(&providers, |p| p.image(png)).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Provider that fails or answers with its own name
+    struct Scripted(&'static str, bool);
+
+    impl OcrProvider for Scripted {
+        fn name(&self) -> &'static str {
+            self.0
+        }
+
+        fn screen<'a>(&'a self, _capture: &'a ScreenCapture) -> OcrFuture<'a> {
+            self.image(&[])
+        }
+
+        fn image<'a>(&'a self, _png: &'a [u8]) -> OcrFuture<'a> {
+            let result = if self.1 {
+                Ok(OcrResult { text: self.0.to_string(), provider: self.0, ..Default::default() })
+            } else {
+                Err("unavailable".to_string())
+            };
+            Box::pin(async move { result })
+        }
+    }
+
+    #[tokio::test]
+    async fn falls_back_in_configured_order() {
+        let providers: Vec<Box<dyn OcrProvider>> = vec![
+            Box::new(Scripted("first", false)),
+            Box::new(Scripted("second", true)),
+            Box::new(Scripted("third", true)),
+        ];
+        let result = first_success(&providers, |p| p.image(&[])).await.unwrap();
+        assert_eq!(result.provider, "second");
+    }
+
+    #[tokio::test]
+    async fn reports_every_failure() {
+        let providers: Vec<Box<dyn OcrProvider>> =
+            vec![Box::new(Scripted("first", false)), Box::new(Scripted("second", false))];
+        let err = first_success(&providers, |p| p.image(&[])).await.unwrap_err();
+        assert_eq!(err, "first: unavailable; second: unavailable");
+        assert!(first_success(&[], |p| p.image(&[])).await.is_err());
+    }
+
+    #[test]
+    fn normalizes_provider_json() {
+        let vision = serde_json::json!({ "text": "Hello", "appName": "Notes", "confidence": 0.92 });
+        let result = OcrResult::from_json(&vision, "vision");
+        assert_eq!((result.text.as_str(), result.app_name.as_str()), ("Hello", "Notes"));
+        assert_eq!(result.confidence, Some(0.92));
+
+        let server = serde_json::json!({ "text": "Hello", "confidence": 87 });
+        assert_eq!(OcrResult::from_json(&server, "server").confidence, Some(0.87));
+        assert_eq!(OcrResult::from_json(&serde_json::json!({}), "server").confidence, None);
+    }
+
+    fn display(id: u32, x: f64) -> DisplayInfo {
+        DisplayInfo {
+            id,
+            name: format!("Display {}", id),
+            bounds: Rect { x, y: 0.0, width: 1920.0, height: 1080.0 },
+            scale_factor: 1.0,
+            primary: x == 0.0,
+        }
+    }
+
+    #[test]
+    fn window_capture_needs_window_bounds() {
+        let displays = vec![display(1, 0.0), display(2, 1920.0)];
+        let window = ActiveWindowInfo { x: 2000.0, y: 50.0, width: 800.0, height: 600.0, ..Default::default() };
+        let request = ScreenRequest::new(CaptureArea::ActiveWindow, Some(&window), &displays, 0);
+        assert_eq!(request.area, CaptureArea::ActiveWindow);
+        assert_eq!(request.window, Some(window.bounds()));
+        assert_eq!(request.display.map(|d| d.id), Some(2));
+
+        // Minimized windows and apps without a window report no size
+        let minimized = ActiveWindowInfo { width: 0.0, height: 0.0, ..window.clone() };
+        let request = ScreenRequest::new(CaptureArea::ActiveWindow, Some(&minimized), &displays, 0);
+        assert_eq!((request.area, request.window), (CaptureArea::ActiveMonitor, None));
+        assert_eq!(request.display.as_ref().map(|d| d.id), Some(1));
+        let all = ScreenRequest::new(CaptureArea::AllMonitors, Some(&window), &displays, 0);
+        assert_eq!((all.area, all.display), (CaptureArea::AllMonitors, None));
+
+        let geometry = CaptureGeometry { area: request.area, bounds: window.bounds(), display: None };
+        assert_eq!(
+            serde_json::json!(geometry),
+            serde_json::json!({ "area": "active_monitor", "x": 2000.0, "y": 50.0, "width": 800.0, "height": 600.0 })
+        );
+        let geometry = CaptureGeometry { display: request.display, ..geometry };
+        assert_eq!(serde_json::json!(geometry)["display"]["id"], 1);
+    }
+
+    #[test]
+    fn rotation_takes_each_display_in_turn() {
+        let displays = vec![display(1, 0.0), display(2, 1920.0), display(3, 3840.0)];
+        let ids: Vec<u32> = (0..4)
+            .filter_map(|turn| ScreenRequest::new(CaptureArea::RotateMonitors, None, &displays, turn).display)
+            .map(|d| d.id)
+            .collect();
+        assert_eq!(ids, vec![1, 2, 3, 1]);
+        // No display list: the provider picks the primary display
+        assert_eq!(ScreenRequest::new(CaptureArea::RotateMonitors, None, &[], 5).display, None);
+    }
+
+    #[test]
+    fn server_is_only_a_default_without_local_providers() {
+        let defaults = OcrSettings::default().providers;
+        let local = available().len() > 1;
+        assert_eq!(defaults.contains(&"server".to_string()), !local);
+        assert!(!defaults.is_empty());
+    }
+
+    #[test]
+    fn settings_only_accept_built_in_providers() {
+        let settings = OcrSettings {
+            providers: vec![" Server ".to_string(), "server".to_string()],
+            tesseract_language: String::new(),
+            vision_languages: vec![" en-US ".to_string(), String::new()],
+            capture_area: CaptureArea::ActiveMonitor,
+            upload: Default::default(),
+        };
+        let settings = validate(settings).unwrap();
+        assert_eq!(settings.providers, vec!["server"]);
+        assert_eq!(settings.tesseract_language, "eng");
+        assert_eq!(settings.vision_languages, vec!["en-US"]);
+
+        let unknown = OcrSettings { providers: vec!["abbyy".to_string()], ..Default::default() };
+        assert!(validate(unknown).is_err());
+        let empty = OcrSettings { providers: Vec::new(), ..Default::default() };
+        assert!(validate(empty).is_err());
+        let blurry = OcrSettings {
+            upload: upload::UploadSettings { target_text_height: 100, ..Default::default() },
+            ..Default::default()
+        };
+        assert!(validate(blurry).is_err());
+    }
+}
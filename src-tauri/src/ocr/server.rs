@@ -0,0 +1,107 @@
+use super::upload::UploadSettings;
+use super::{OcrFuture, OcrProvider, OcrResult, ScreenCapture};
+use base64::Engine;
+use tauri_plugin_store::StoreExt;
+
+/// Server-side Tesseract (POST /api/tray/ocr). Needs an API token and sends
+/// the image off the machine, so it is the last resort in the default order.
+pub struct ServerProvider {
+    app: tauri::AppHandle,
+    #[cfg_attr(target_os = "macos", allow(dead_code))]
+    upload: UploadSettings,
+}
+
+impl ServerProvider {
+    pub fn new(app: tauri::AppHandle, upload: UploadSettings) -> Self {
+        Self { app, upload }
+    }
+
+    /// Send an encoded image to the server OCR endpoint
+    async fn ocr(&self, image: &[u8], mime_type: &str, app_name: String) -> Result<OcrResult, String> {
+        let store = self.app.store("config.json").map_err(|e| e.to_string())?;
+        let url = store
+            .get("api_url")
+            .and_then(|v| v.as_str().map(|s| s.to_string()))
+            .unwrap_or_else(|| "https://reattend.com".to_string());
+        let token = store
+            .get("api_token")
+            .and_then(|v| v.as_str().map(|s| s.to_string()))
+            .unwrap_or_default();
+
+        if token.is_empty() {
+            return Err("No API token configured".to_string());
+        }
+
+        let client = reqwest::Client::new();
+        let resp = client
+            .post(format!("{}/api/tray/ocr", url))
+            .header("Authorization", format!("Bearer {}", token))
+            .json(&serde_json::json!({
+                "image": base64::engine::general_purpose::STANDARD.encode(image),
+                "mime_type": mime_type,
+                "app_name": &app_name,
+            }))
+            .timeout(std::time::Duration::from_secs(30))
+            .send()
+            .await
+            .map_err(|e| format!("OCR request failed: {}", e))?;
+
+        if !resp.status().is_success() {
+            return Err(format!("OCR API error: {}", resp.status()));
+        }
+
+        let value: serde_json::Value = resp.json().await.map_err(|e| e.to_string())?;
+        let mut result = OcrResult::from_json(&value, "server");
+        if result.app_name.is_empty() {
+            result.app_name = app_name;
+        }
+        Ok(result)
+    }
+}
+
+impl OcrProvider for ServerProvider {
+    fn name(&self) -> &'static str {
+        "server"
+    }
+
+    #[cfg(any(target_os = "windows", target_os = "linux"))]
+    fn screen<'a>(&'a self, capture: &'a ScreenCapture) -> OcrFuture<'a> {
+        Box::pin(async move {
+            let image = capture.image.clone();
+            let pixels_per_point = image.width() as f64 / capture.geometry.bounds.width.max(1.0);
+            let settings = self.upload.clone();
+            let prepared = tokio::task::spawn_blocking(move || super::upload::prepare(&image, pixels_per_point, &settings))
+                .await
+                .map_err(|e| format!("Encode task panicked: {}", e))??;
+            let result = self.ocr(&prepared.data, prepared.format.mime_type(), capture.app_name.clone()).await;
+            // Record the outcome against the size so the settings can be tuned
+            super::upload::record(super::upload::UploadRecord {
+                bytes: prepared.data.len(),
+                format: prepared.format,
+                width: prepared.width,
+                height: prepared.height,
+                text_height: prepared.text_height,
+                measured: prepared.measured,
+                crop_x: prepared.crop.0,
+                crop_y: prepared.crop.1,
+                confidence: result.as_ref().ok().and_then(|r| r.confidence),
+            });
+            let mut result = result?;
+            // Only the cropped part of the screenshot was read
+            let mut geometry = capture.geometry.clone();
+            geometry.bounds = super::upload::cropped_bounds(geometry.bounds, pixels_per_point, prepared.crop);
+            result.geometry = Some(geometry);
+            Ok(result)
+        })
+    }
+
+    #[cfg(target_os = "macos")]
+    fn screen<'a>(&'a self, _capture: &'a ScreenCapture) -> OcrFuture<'a> {
+        Box::pin(async { Err("Screen capture for server OCR is not supported on macOS".to_string()) })
+    }
+
+    /// PNG keeps small text legible
+    fn image<'a>(&'a self, png: &'a [u8]) -> OcrFuture<'a> {
+        Box::pin(self.ocr(png, "image/png", crate::platform::platform_get_active_app_name()))
+    }
+}
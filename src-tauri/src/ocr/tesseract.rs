@@ -0,0 +1,72 @@
+use super::{OcrFuture, OcrProvider, OcrResult, ScreenCapture};
+
+/// Local Tesseract via libtesseract (leptess). Needs Tesseract and its
+/// language data installed; `TESSDATA_PREFIX` overrides where the data is found.
+pub struct TesseractProvider {
+    language: String,
+}
+
+impl TesseractProvider {
+    pub fn new(language: &str) -> Self {
+        Self { language: language.to_string() }
+    }
+
+    /// OCR an encoded image off the async runtime. A fresh engine per call —
+    /// initialising takes ~100ms, negligible next to the capture interval.
+    async fn recognize(&self, image: Vec<u8>, app_name: String) -> Result<OcrResult, String> {
+        let language = self.language.clone();
+        tokio::task::spawn_blocking(move || -> Result<OcrResult, String> {
+            let mut tess = leptess::LepTess::new(None, &language)
+                .map_err(|e| format!("Tesseract init failed ({}): {}", language, e))?;
+            tess.set_image_from_mem(&image)
+                .map_err(|e| format!("Tesseract could not load image: {}", e))?;
+            let text = tess
+                .get_utf8_text()
+                .map_err(|e| format!("Tesseract output error: {}", e))?;
+            Ok(OcrResult {
+                text,
+                app_name,
+                confidence: Some(tess.mean_text_conf().clamp(0, 100) as f64 / 100.0),
+                provider: "tesseract",
+                geometry: None,
+            })
+        })
+        .await
+        .map_err(|e| format!("OCR task panicked: {}", e))?
+    }
+}
+
+impl OcrProvider for TesseractProvider {
+    fn name(&self) -> &'static str {
+        "tesseract"
+    }
+
+    /// Full resolution: Tesseract needs ~10px glyphs, a downscaled screenshot loses small text
+    #[cfg(any(target_os = "windows", target_os = "linux"))]
+    fn screen<'a>(&'a self, capture: &'a ScreenCapture) -> OcrFuture<'a> {
+        Box::pin(async move {
+            let image = capture.image.clone();
+            let png = tokio::task::spawn_blocking(move || -> Result<Vec<u8>, String> {
+                let mut png = std::io::Cursor::new(Vec::new());
+                image
+                    .write_to(&mut png, image::ImageFormat::Png)
+                    .map_err(|e| format!("PNG encode error: {}", e))?;
+                Ok(png.into_inner())
+            })
+            .await
+            .map_err(|e| format!("Encode task panicked: {}", e))??;
+            let mut result = self.recognize(png, capture.app_name.clone()).await?;
+            result.geometry = Some(capture.geometry.clone());
+            Ok(result)
+        })
+    }
+
+    #[cfg(target_os = "macos")]
+    fn screen<'a>(&'a self, _capture: &'a ScreenCapture) -> OcrFuture<'a> {
+        Box::pin(async { Err("Screen capture for Tesseract is not supported on macOS".to_string()) })
+    }
+
+    fn image<'a>(&'a self, png: &'a [u8]) -> OcrFuture<'a> {
+        Box::pin(self.recognize(png.to_vec(), crate::platform::platform_get_active_app_name()))
+    }
+}
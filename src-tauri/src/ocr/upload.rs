@@ -0,0 +1,350 @@
+use crate::platform::Rect;
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::sync::{Mutex, OnceLock};
+
+// Screenshots for server OCR are sized by how tall their text is rather than
+// by a fixed factor: Tesseract reads glyphs of about 20px best, and anything
+// taller only costs upload bytes. The image is cropped to where there is
+// content, optionally made grayscale and encoded losslessly, since JPEG
+// artifacts around small glyphs hurt recognition more than they save.
+
+/// Line height assumed for UI text, in points, when none can be measured
+const TYPICAL_TEXT_POINTS: f64 = 16.0;
+
+/// Brightness step between neighbouring pixels that counts as an edge
+const EDGE_THRESHOLD: u8 = 48;
+
+/// Border kept around the content when cropping, in pixels
+const CROP_MARGIN: u32 = 8;
+
+/// Uploads kept for the stats
+const RECENT_UPLOADS: usize = 100;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum UploadFormat {
+    #[default]
+    Png,
+    /// Lossless WebP: usually smaller than PNG, slower to encode
+    Webp,
+}
+
+impl UploadFormat {
+    pub fn mime_type(self) -> &'static str {
+        match self {
+            UploadFormat::Png => "image/png",
+            UploadFormat::Webp => "image/webp",
+        }
+    }
+}
+
+/// How screenshots are prepared for server OCR (config.json → "ocr" → "upload")
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct UploadSettings {
+    pub format: UploadFormat,
+    pub grayscale: bool,
+    /// Drop empty borders around the content
+    pub crop_to_content: bool,
+    /// Text line height to scale screenshots to, in pixels. Screenshots are
+    /// only ever scaled down.
+    pub target_text_height: u32,
+    /// Upper bound on uploaded pixels, whatever the text height
+    pub max_pixels: u32,
+}
+
+impl Default for UploadSettings {
+    fn default() -> Self {
+        Self {
+            format: UploadFormat::Png,
+            grayscale: true,
+            crop_to_content: true,
+            target_text_height: 20,
+            max_pixels: 4_000_000,
+        }
+    }
+}
+
+impl UploadSettings {
+    pub fn validate(&self) -> Result<(), String> {
+        if !(8..=64).contains(&self.target_text_height) {
+            return Err("target_text_height must be between 8 and 64 pixels".to_string());
+        }
+        if self.max_pixels < 100_000 {
+            return Err("max_pixels must be at least 100000".to_string());
+        }
+        Ok(())
+    }
+}
+
+/// Row-major luma pixels with a per-pixel edge test
+struct Luma<'a> {
+    pixels: &'a [u8],
+    width: u32,
+    height: u32,
+}
+
+impl Luma<'_> {
+    fn at(&self, x: u32, y: u32) -> u8 {
+        self.pixels[(y * self.width + x) as usize]
+    }
+
+    /// Whether the pixel differs sharply from its right or lower neighbour
+    fn is_edge(&self, x: u32, y: u32) -> bool {
+        let p = self.at(x, y);
+        (x + 1 < self.width && p.abs_diff(self.at(x + 1, y)) >= EDGE_THRESHOLD)
+            || (y + 1 < self.height && p.abs_diff(self.at(x, y + 1)) >= EDGE_THRESHOLD)
+    }
+
+    /// Horizontal brightness steps per row; glyphs make many, so rows of
+    /// text stand out from the rows between them
+    fn row_steps(&self) -> Vec<u32> {
+        (0..self.height)
+            .map(|y| {
+                (1..self.width)
+                    .filter(|&x| self.at(x - 1, y).abs_diff(self.at(x, y)) >= EDGE_THRESHOLD)
+                    .count() as u32
+            })
+            .collect()
+    }
+}
+
+/// Smallest `(x, y, width, height)` holding every edge, plus a margin.
+/// None for a blank image.
+fn content_bounds(luma: &Luma) -> Option<(u32, u32, u32, u32)> {
+    let (mut left, mut top, mut right, mut bottom) = (u32::MAX, u32::MAX, 0, 0);
+    for y in 0..luma.height {
+        for x in 0..luma.width {
+            if luma.is_edge(x, y) {
+                left = left.min(x);
+                right = right.max(x + 1);
+                top = top.min(y);
+                bottom = bottom.max(y + 1);
+            }
+        }
+    }
+    if left == u32::MAX {
+        return None;
+    }
+    let x = left.saturating_sub(CROP_MARGIN);
+    let y = top.saturating_sub(CROP_MARGIN);
+    let right = (right + CROP_MARGIN).min(luma.width);
+    let bottom = (bottom + CROP_MARGIN).min(luma.height);
+    Some((x, y, right - x, bottom - y))
+}
+
+/// Median height of the bands of rows with brightness steps in them, which
+/// on a screen of text are its lines. None with fewer than three plausible lines.
+fn text_height(luma: &Luma) -> Option<f64> {
+    // A handful of steps is a border or an icon, not text
+    let min_steps = (luma.width / 100).max(4);
+    let mut lines = Vec::new();
+    let mut run = 0;
+    for steps in luma.row_steps().into_iter().chain(std::iter::once(0)) {
+        if steps >= min_steps {
+            run += 1;
+            continue;
+        }
+        if (4..=200).contains(&run) {
+            lines.push(run);
+        }
+        run = 0;
+    }
+    if lines.len() < 3 {
+        return None;
+    }
+    lines.sort_unstable();
+    Some(lines[lines.len() / 2] as f64)
+}
+
+/// Scale that brings `text_height` down to the target and the image within
+/// `max_pixels`; never above 1
+fn scale_for(text_height: f64, width: u32, height: u32, settings: &UploadSettings) -> f64 {
+    let by_text = settings.target_text_height as f64 / text_height;
+    let by_size = (settings.max_pixels as f64 / (width as f64 * height as f64)).sqrt();
+    by_text.min(by_size).min(1.0)
+}
+
+/// A screenshot encoded for upload
+pub struct Upload {
+    pub data: Vec<u8>,
+    pub format: UploadFormat,
+    pub width: u32,
+    pub height: u32,
+    /// Line height the scale was based on, in source pixels
+    pub text_height: f64,
+    /// Whether `text_height` was measured rather than assumed from the DPI
+    pub measured: bool,
+    /// Area kept by the crop, in source pixels: `(x, y, width, height)`
+    pub crop: (u32, u32, u32, u32),
+}
+
+/// Screen area of `crop` within a capture of `bounds` taken at `pixels_per_point`
+pub fn cropped_bounds(bounds: Rect, pixels_per_point: f64, crop: (u32, u32, u32, u32)) -> Rect {
+    let (x, y, width, height) = crop;
+    let scale = pixels_per_point.max(f64::EPSILON);
+    Rect {
+        x: bounds.x + x as f64 / scale,
+        y: bounds.y + y as f64 / scale,
+        width: width as f64 / scale,
+        height: height as f64 / scale,
+    }
+}
+
+/// Crop, scale and encode a screenshot for server OCR. `pixels_per_point` is
+/// the capture's DPI scale, used when no text lines can be measured.
+pub fn prepare(image: &image::RgbaImage, pixels_per_point: f64, settings: &UploadSettings) -> Result<Upload, String> {
+    let gray = image::imageops::grayscale(image);
+    let luma = Luma { pixels: gray.as_raw(), width: gray.width(), height: gray.height() };
+    let whole = (0, 0, gray.width(), gray.height());
+    let (x, y, width, height) =
+        if settings.crop_to_content { content_bounds(&luma).unwrap_or(whole) } else { whole };
+    let measured = text_height(&luma);
+    let text_height = measured.unwrap_or(TYPICAL_TEXT_POINTS * pixels_per_point);
+
+    let image = if settings.grayscale {
+        image::DynamicImage::ImageLuma8(image::imageops::crop_imm(&gray, x, y, width, height).to_image())
+    } else {
+        let cropped = image::imageops::crop_imm(image, x, y, width, height).to_image();
+        image::DynamicImage::ImageRgb8(image::DynamicImage::ImageRgba8(cropped).into_rgb8())
+    };
+    let scale = scale_for(text_height, width, height, settings);
+    let image = if scale < 0.99 {
+        image.resize_exact(
+            ((width as f64 * scale).round() as u32).max(1),
+            ((height as f64 * scale).round() as u32).max(1),
+            image::imageops::FilterType::Triangle,
+        )
+    } else {
+        image
+    };
+
+    let mut buf = std::io::Cursor::new(Vec::new());
+    let format = match settings.format {
+        UploadFormat::Png => image::ImageFormat::Png,
+        UploadFormat::Webp => image::ImageFormat::WebP,
+    };
+    image
+        .write_to(&mut buf, format)
+        .map_err(|e| format!("Image encode error: {}", e))?;
+    Ok(Upload {
+        data: buf.into_inner(),
+        format: settings.format,
+        width: image.width(),
+        height: image.height(),
+        text_height,
+        measured: measured.is_some(),
+        crop: (x, y, width, height),
+    })
+}
+
+/// One server OCR upload and how well it was read
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UploadRecord {
+    pub bytes: usize,
+    pub format: UploadFormat,
+    pub width: u32,
+    pub height: u32,
+    pub text_height: f64,
+    pub measured: bool,
+    /// Top-left corner of the cropped area within the screenshot, in pixels
+    pub crop_x: u32,
+    pub crop_y: u32,
+    /// Server's mean recognition confidence in 0..=1
+    pub confidence: Option<f64>,
+}
+
+/// Counters for tuning the upload settings
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UploadStats {
+    pub uploads: u64,
+    pub total_bytes: u64,
+    /// Most recent uploads, oldest first
+    pub recent: VecDeque<UploadRecord>,
+}
+
+impl UploadStats {
+    fn record(&mut self, record: UploadRecord) {
+        self.uploads += 1;
+        self.total_bytes += record.bytes as u64;
+        if self.recent.len() == RECENT_UPLOADS {
+            self.recent.pop_front();
+        }
+        self.recent.push_back(record);
+    }
+}
+
+fn upload_stats() -> std::sync::MutexGuard<'static, UploadStats> {
+    static STATS: OnceLock<Mutex<UploadStats>> = OnceLock::new();
+    STATS.get_or_init(Default::default).lock().unwrap_or_else(|e| e.into_inner())
+}
+
+pub fn record(record: UploadRecord) {
+    upload_stats().record(record);
+}
+
+pub fn stats() -> UploadStats {
+    upload_stats().clone()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// White page with dark 12px-tall "lines" of glyph stripes every 30px,
+    /// from `top` down
+    fn page(width: u32, height: u32, top: u32, lines: u32) -> Vec<u8> {
+        let mut pixels = vec![255u8; (width * height) as usize];
+        for line in 0..lines {
+            for y in top + line * 30..top + line * 30 + 12 {
+                for x in (20..width - 20).filter(|x| x % 4 == 0) {
+                    pixels[(y * width + x) as usize] = 0;
+                }
+            }
+        }
+        pixels
+    }
+
+    #[test]
+    fn measures_text_lines_and_crops_to_them() {
+        let pixels = page(400, 300, 50, 5);
+        let luma = Luma { pixels: &pixels, width: 400, height: 300 };
+        assert_eq!(text_height(&luma), Some(12.0));
+        // Lines span x 20..377 and y 50..182; their edges start a pixel
+        // before that, then the margin is added
+        assert_eq!(content_bounds(&luma), Some((11, 41, 374, 149)));
+
+        // Too few lines to tell
+        let pixels = page(400, 300, 50, 2);
+        assert_eq!(text_height(&Luma { pixels: &pixels, width: 400, height: 300 }), None);
+        let blank = vec![255u8; 400 * 300];
+        assert_eq!(content_bounds(&Luma { pixels: &blank, width: 400, height: 300 }), None);
+    }
+
+    #[test]
+    fn scales_down_to_the_target_text_height_only() {
+        let settings = UploadSettings::default();
+        // 1080p at 1x: 16px lines are already small, keep every pixel
+        assert_eq!(scale_for(16.0, 1920, 1080, &settings), 1.0);
+        // 4K at 2x: 32px lines shrink to 20px
+        assert!((scale_for(32.0, 3840, 2160, &settings) - 0.625).abs() < 1e-9);
+        // Pixel cap wins over huge stitched captures
+        let capped = scale_for(20.0, 7680, 2160, &settings);
+        assert!((7680.0 * capped) * (2160.0 * capped) <= 4_000_001.0);
+
+        assert!(UploadSettings { target_text_height: 4, ..Default::default() }.validate().is_err());
+        assert!(settings.validate().is_ok());
+    }
+
+    #[test]
+    fn crops_map_back_to_screen_points() {
+        let window = Rect { x: 100.0, y: 50.0, width: 800.0, height: 600.0 };
+        // A 2x capture cropped to the 374x149 pixels from (11, 41)
+        let bounds = cropped_bounds(window, 2.0, (11, 41, 374, 149));
+        assert_eq!(bounds, Rect { x: 105.5, y: 70.5, width: 187.0, height: 74.5 });
+        assert_eq!(cropped_bounds(window, 1.0, (0, 0, 800, 600)), window);
+    }
+}
@@ -0,0 +1,54 @@
+use super::{OcrFuture, OcrProvider, OcrResult, ScreenCapture};
+use crate::platform::capture_helper;
+use std::io::Write;
+use std::time::Duration;
+
+/// OCR of a full-screen capture; Vision on a large Retina display can take a while
+const SCREEN_TIMEOUT: Duration = Duration::from_secs(20);
+const IMAGE_TIMEOUT: Duration = Duration::from_secs(15);
+
+/// Apple Vision via the reattend-capture Swift helper. Runs on-device.
+pub struct VisionProvider {
+    app: tauri::AppHandle,
+    languages: Vec<String>,
+}
+
+impl VisionProvider {
+    pub fn new(app: tauri::AppHandle, languages: &[String]) -> Self {
+        Self { app, languages: languages.to_vec() }
+    }
+}
+
+impl OcrProvider for VisionProvider {
+    fn name(&self) -> &'static str {
+        "vision"
+    }
+
+    fn screen<'a>(&'a self, capture: &'a ScreenCapture) -> OcrFuture<'a> {
+        Box::pin(async move {
+            let params = serde_json::json!({ "id": capture.id, "languages": self.languages });
+            let value = capture_helper::get(&self.app).call("ocr_capture", params, SCREEN_TIMEOUT).await?;
+            let mut result = OcrResult::from_json(&value, "vision");
+            result.app_name = capture.app_name.clone();
+            result.geometry = Some(capture.geometry.clone());
+            Ok(result)
+        })
+    }
+
+    fn image<'a>(&'a self, png: &'a [u8]) -> OcrFuture<'a> {
+        Box::pin(async move {
+            // Hand the image over as a temporary PNG: a new owner-only file
+            // per call, deleted when dropped, so concurrent reads can't mix
+            let mut file = tempfile::Builder::new()
+                .prefix("reattend-clip-")
+                .suffix(".png")
+                .tempfile()
+                .map_err(|e| format!("Failed to create temp image: {}", e))?;
+            file.write_all(png).map_err(|e| format!("Failed to write temp image: {}", e))?;
+
+            let params = serde_json::json!({ "path": file.path(), "languages": self.languages });
+            let value = capture_helper::get(&self.app).call("ocr_image", params, IMAGE_TIMEOUT).await?;
+            Ok(OcrResult::from_json(&value, "vision"))
+        })
+    }
+}
@@ -0,0 +1,434 @@
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, HashMap};
+use tauri_plugin_store::StoreExt;
+
+/// How a detected entity is treated before upload
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum PiiMode {
+    /// Leave the text untouched
+    Keep,
+    /// Replace with a type placeholder, e.g. `[EMAIL]`
+    Mask,
+    /// Replace with a salted hash, e.g. `[EMAIL:3fa2c91b]` — same value, same token
+    Hash,
+}
+
+/// Entity types: (id, description, placeholder label)
+pub const PII_ENTITIES: &[(&str, &str, &str)] = &[
+    ("email", "Email addresses", "EMAIL"),
+    ("phone", "Phone numbers", "PHONE"),
+    ("iban", "IBAN bank account numbers", "IBAN"),
+    ("national_id", "National ID numbers (US SSN, UK NINO)", "NATIONAL_ID"),
+    ("street_address", "Street addresses", "ADDRESS"),
+];
+
+/// Street-type words that end a street address ("221 Baker Street")
+const STREET_SUFFIXES: &[&str] = &[
+    "street", "avenue", "ave", "road", "rd", "boulevard", "blvd", "lane", "ln",
+    "terrace", "parkway", "pkwy", "highway", "hwy", "crescent",
+];
+
+/// Street-type words that are also common in prose ("the Supreme Court",
+/// "10 Best Way"); these only end an address that goes on with a comma, a
+/// compass direction or a postcode ("12 Kings Court, Leeds")
+const AMBIGUOUS_STREET_SUFFIXES: &[&str] = &[
+    "st", "drive", "dr", "court", "ct", "way", "place", "pl", "square", "sq", "close",
+];
+
+/// Directions that follow a street name ("1600 Pennsylvania Ave NW")
+const COMPASS_POINTS: &[&str] = &["n", "e", "s", "w", "ne", "nw", "se", "sw"];
+
+/// Street-name endings that precede the house number ("Hauptstraße 5")
+const STREET_NAME_ENDINGS: &[&str] = &[
+    "straße", "strasse", "str.", "weg", "gasse", "platz", "allee", "laan", "straat", "gatan",
+];
+
+/// One detected entity, as a byte range into the text
+#[derive(Debug, Clone)]
+struct PiiHit {
+    entity: &'static str,
+    start: usize,
+    end: usize,
+}
+
+/// Load per-entity modes (config.json → "pii_redaction"). Everything is masked by default.
+pub fn load_modes(app: &tauri::AppHandle) -> HashMap<String, PiiMode> {
+    let configured: HashMap<String, PiiMode> = app
+        .store("config.json")
+        .ok()
+        .and_then(|s| s.get("pii_redaction"))
+        .and_then(|v| serde_json::from_value(v).ok())
+        .unwrap_or_default();
+
+    PII_ENTITIES
+        .iter()
+        .map(|(id, _, _)| (id.to_string(), configured.get(*id).copied().unwrap_or(PiiMode::Mask)))
+        .collect()
+}
+
+/// Persist per-entity modes. Unknown entity types are ignored.
+pub fn save_modes(app: &tauri::AppHandle, modes: HashMap<String, PiiMode>) -> Result<(), String> {
+    let modes: HashMap<String, PiiMode> = modes
+        .into_iter()
+        .filter(|(id, _)| PII_ENTITIES.iter().any(|(entity, _, _)| entity == id))
+        .collect();
+    let store = app.store("config.json").map_err(|e| e.to_string())?;
+    store.set("pii_redaction", serde_json::json!(modes));
+    store.save().map_err(|e| e.to_string())
+}
+
+/// Per-install salt for hash mode, created on first use.
+/// Without it, hashed emails could be reversed with a dictionary.
+pub fn install_salt(app: &tauri::AppHandle) -> String {
+    let store = match app.store("config.json") {
+        Ok(s) => s,
+        Err(_) => return String::new(),
+    };
+    if let Some(salt) = store.get("pii_salt").and_then(|v| v.as_str().map(|s| s.to_string())) {
+        return salt;
+    }
+    // RandomState is seeded from the OS RNG — two of them give 128 random bits
+    use std::hash::{BuildHasher, Hasher};
+    let a = std::collections::hash_map::RandomState::new().build_hasher().finish();
+    let b = std::collections::hash_map::RandomState::new().build_hasher().finish();
+    let salt = format!("{:016x}{:016x}", a, b);
+    store.set("pii_salt", serde_json::json!(salt));
+    let _ = store.save();
+    salt
+}
+
+/// Redact PII according to the configured modes.
+/// Returns the new text and how many entities of each type were replaced.
+pub fn redact(text: &str, modes: &HashMap<String, PiiMode>, salt: &str) -> (String, BTreeMap<String, usize>) {
+    let mut hits = Vec::new();
+    let enabled = |entity: &str| modes.get(entity).copied().unwrap_or(PiiMode::Mask) != PiiMode::Keep;
+    if enabled("email") { scan_emails(text, &mut hits); }
+    if enabled("iban") { scan_ibans(text, &mut hits); }
+    if enabled("national_id") { scan_national_ids(text, &mut hits); }
+    if enabled("phone") { scan_phones(text, &mut hits); }
+    if enabled("street_address") { scan_street_addresses(text, &mut hits); }
+
+    hits.sort_by(|a, b| a.start.cmp(&b.start).then(b.end.cmp(&a.end)));
+
+    let mut out = String::with_capacity(text.len());
+    let mut counts: BTreeMap<String, usize> = BTreeMap::new();
+    let mut cursor = 0;
+    for hit in hits {
+        if hit.start < cursor {
+            continue; // overlaps an earlier replacement
+        }
+        let label = PII_ENTITIES
+            .iter()
+            .find(|(id, _, _)| *id == hit.entity)
+            .map(|(_, _, label)| *label)
+            .unwrap_or("PII");
+        out.push_str(&text[cursor..hit.start]);
+        match modes.get(hit.entity).copied().unwrap_or(PiiMode::Mask) {
+            PiiMode::Hash => {
+                let value = &text[hit.start..hit.end];
+                out.push_str(&format!("[{}:{}]", label, hash_value(salt, hit.entity, value)));
+            }
+            _ => out.push_str(&format!("[{}]", label)),
+        }
+        *counts.entry(hit.entity.to_string()).or_insert(0) += 1;
+        cursor = hit.end;
+    }
+    out.push_str(&text[cursor..]);
+    (out, counts)
+}
+
+/// Short salted hash of a normalized value (case and separators ignored)
+fn hash_value(salt: &str, entity: &str, value: &str) -> String {
+    let normalized: String = value
+        .chars()
+        .filter(|c| c.is_alphanumeric() || *c == '@' || *c == '.')
+        .flat_map(|c| c.to_lowercase())
+        .collect();
+    let mut hasher = Sha256::new();
+    hasher.update(salt.as_bytes());
+    hasher.update(entity.as_bytes());
+    hasher.update(normalized.as_bytes());
+    hasher.finalize()[..4].iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// Whitespace-separated words with their byte ranges, trailing punctuation trimmed
+fn words(text: &str) -> Vec<(usize, usize, &str)> {
+    let mut out = Vec::new();
+    let mut start = None;
+    for (i, c) in text.char_indices().chain(std::iter::once((text.len(), ' '))) {
+        if c.is_whitespace() {
+            if let Some(s) = start.take() {
+                let word = text[s..i].trim_end_matches([',', ';', ':', ')', '!', '?']);
+                if !word.is_empty() {
+                    out.push((s, s + word.len(), word));
+                }
+            }
+        } else if start.is_none() {
+            start = Some(i);
+        }
+    }
+    out
+}
+
+fn scan_emails(text: &str, hits: &mut Vec<PiiHit>) {
+    let is_local = |c: char| c.is_ascii_alphanumeric() || matches!(c, '.' | '_' | '%' | '+' | '-');
+    let is_domain = |c: char| c.is_ascii_alphanumeric() || c == '.' || c == '-';
+    for (at, _) in text.match_indices('@') {
+        let local_len: usize = text[..at].chars().rev().take_while(|c| is_local(*c)).map(|c| c.len_utf8()).sum();
+        let domain_len: usize = text[at + 1..].chars().take_while(|c| is_domain(*c)).map(|c| c.len_utf8()).sum();
+        let domain = text[at + 1..at + 1 + domain_len].trim_end_matches(['.', '-']);
+        let tld_ok = domain
+            .rsplit_once('.')
+            .map(|(host, tld)| !host.is_empty() && tld.len() >= 2 && tld.chars().all(|c| c.is_ascii_alphabetic()))
+            .unwrap_or(false);
+        if local_len > 0 && tld_ok {
+            hits.push(PiiHit { entity: "email", start: at - local_len, end: at + 1 + domain.len() });
+        }
+    }
+}
+
+fn scan_phones(text: &str, hits: &mut Vec<PiiHit>) {
+    let bytes = text.as_bytes();
+    let mut i = 0;
+    while i < bytes.len() {
+        let b = bytes[i];
+        let starts = b == b'+' || b == b'(' || b.is_ascii_digit();
+        if !starts || (i > 0 && (bytes[i - 1].is_ascii_alphanumeric() || bytes[i - 1] == b'[')) {
+            i += 1;
+            continue;
+        }
+        let start = i;
+        let mut end = i;
+        while i < bytes.len() {
+            match bytes[i] {
+                b'0'..=b'9' => { i += 1; end = i; }
+                b'+' if i == start => { i += 1; }
+                b'(' | b')' => { i += 1; if bytes[i - 1] == b')' { end = i; } }
+                b' ' | b'-' | b'.' if i + 1 < bytes.len() && (bytes[i + 1].is_ascii_digit() || bytes[i + 1] == b'(') => {
+                    i += 1;
+                }
+                _ => break,
+            }
+        }
+        let followed_by_word = end < bytes.len() && bytes[end].is_ascii_alphabetic();
+        if !followed_by_word && is_phone(&text[start..end]) {
+            hits.push(PiiHit { entity: "phone", start, end });
+        }
+        if i == start { i += 1; }
+    }
+}
+
+/// Whether a run of digits and separators is laid out like a phone number
+/// rather than a date, timestamp or order number: international ("+44 20
+/// 7946 0958"), national with a trunk 0 ("030 1234567"), or North American
+/// 3-3-4 ("(415) 555-0132", "1-415-555-0132")
+fn is_phone(candidate: &str) -> bool {
+    let groups: Vec<&str> = candidate
+        .split(|c: char| !c.is_ascii_digit())
+        .filter(|g| !g.is_empty())
+        .collect();
+    let lens: Vec<usize> = groups.iter().map(|g| g.len()).collect();
+    let digits: usize = lens.iter().sum();
+    if candidate.starts_with('+') {
+        return (9..=15).contains(&digits);
+    }
+    if lens.len() < 2 {
+        return false;
+    }
+    // 2024-01-15 …, 15.01.2024 …
+    if lens.starts_with(&[4, 2, 2]) || lens.starts_with(&[2, 2, 4]) {
+        return false;
+    }
+    if groups[0].starts_with('0') {
+        return (2..=5).contains(&lens[0]) && (10..=11).contains(&digits);
+    }
+    lens == [3, 3, 4] || (lens == [1, 3, 3, 4] && groups[0] == "1")
+}
+
+/// ISO 13616 mod-97 check
+fn iban_valid(iban: &str) -> bool {
+    if iban.len() < 15 || iban.len() > 34 {
+        return false;
+    }
+    let rearranged = format!("{}{}", &iban[4..], &iban[..4]);
+    let mut remainder: u32 = 0;
+    for c in rearranged.chars() {
+        let value = match c {
+            '0'..='9' => c as u32 - '0' as u32,
+            'A'..='Z' => c as u32 - 'A' as u32 + 10,
+            _ => return false,
+        };
+        remainder = if value >= 10 {
+            (remainder * 100 + value) % 97
+        } else {
+            (remainder * 10 + value) % 97
+        };
+    }
+    remainder == 1
+}
+
+fn scan_ibans(text: &str, hits: &mut Vec<PiiHit>) {
+    let ws = words(text);
+    for (idx, &(start, _, word)) in ws.iter().enumerate() {
+        let b = word.as_bytes();
+        if b.len() < 4
+            || !b[0].is_ascii_uppercase() || !b[1].is_ascii_uppercase()
+            || !b[2].is_ascii_digit() || !b[3].is_ascii_digit()
+        {
+            continue;
+        }
+        // Either one compact word, or groups of four separated by spaces
+        let mut compact = word.to_string();
+        let mut end = start + word.len();
+        if !iban_valid(&compact) {
+            for &(_, group_end, group) in ws.iter().skip(idx + 1) {
+                if group.len() > 4 || !group.chars().all(|c| c.is_ascii_uppercase() || c.is_ascii_digit()) {
+                    break;
+                }
+                compact.push_str(group);
+                end = group_end;
+                if iban_valid(&compact) || compact.len() >= 34 {
+                    break;
+                }
+            }
+        }
+        if iban_valid(&compact) {
+            hits.push(PiiHit { entity: "iban", start, end });
+        }
+    }
+}
+
+fn scan_national_ids(text: &str, hits: &mut Vec<PiiHit>) {
+    for &(start, end, word) in &words(text) {
+        let b = word.as_bytes();
+        // US SSN: 123-45-6789, excluding never-issued area numbers
+        if b.len() == 11 && b[3] == b'-' && b[6] == b'-'
+            && b.iter().enumerate().all(|(i, c)| i == 3 || i == 6 || c.is_ascii_digit())
+        {
+            let area = &word[..3];
+            if area != "000" && area != "666" && !area.starts_with('9') {
+                hits.push(PiiHit { entity: "national_id", start, end });
+            }
+            continue;
+        }
+        // UK National Insurance number: AB123456C
+        if b.len() == 9
+            && b[..2].iter().all(|c| c.is_ascii_uppercase() && !b"DFIQUV".contains(c))
+            && b[2..8].iter().all(|c| c.is_ascii_digit())
+            && (b'A'..=b'D').contains(&b[8])
+        {
+            hits.push(PiiHit { entity: "national_id", start, end });
+        }
+    }
+}
+
+fn scan_street_addresses(text: &str, hits: &mut Vec<PiiHit>) {
+    let ws = words(text);
+    let is_number = |w: &str| {
+        let digits = w.chars().take_while(|c| c.is_ascii_digit()).count();
+        (1..=6).contains(&digits) && w.len() - digits <= 1
+    };
+    let is_year = |w: &str| w.len() == 4 && (w.starts_with("19") || w.starts_with("20"));
+    let is_capitalized = |w: &str| w.chars().next().map(|c| c.is_uppercase()).unwrap_or(false);
+    // What follows an ambiguous suffix must show the address goes on
+    let continues = |suffix_end: usize, next: Option<&(usize, usize, &str)>| {
+        text[suffix_end..].starts_with(',')
+            || next.is_some_and(|&(_, _, w)| {
+                COMPASS_POINTS.contains(&w.to_lowercase().as_str()) || (is_number(w) && w.len() >= 4)
+            })
+    };
+
+    for (idx, &(start, _, word)) in ws.iter().enumerate() {
+        // "221B Baker Street", "1600 Pennsylvania Ave NW"
+        if is_number(word) {
+            for (offset, &(_, end, next)) in ws.iter().enumerate().skip(idx + 1).take(5) {
+                let lower = next.trim_end_matches('.').to_lowercase();
+                if offset > idx + 1 {
+                    if STREET_SUFFIXES.contains(&lower.as_str()) {
+                        hits.push(PiiHit { entity: "street_address", start, end });
+                        break;
+                    }
+                    if AMBIGUOUS_STREET_SUFFIXES.contains(&lower.as_str())
+                        && !is_year(word)
+                        && continues(end, ws.get(offset + 1))
+                    {
+                        hits.push(PiiHit { entity: "street_address", start, end });
+                        break;
+                    }
+                }
+                if !is_capitalized(next) {
+                    break;
+                }
+            }
+        }
+        // "Hauptstraße 5", "Keizersgracht Straat 12"; the ending must follow
+        // a name, so "Platz 1" (first place) is not an address
+        let lower = word.to_lowercase();
+        if is_capitalized(word) && STREET_NAME_ENDINGS.iter().any(|e| lower.ends_with(e) && lower.len() > e.len()) {
+            if let Some(&(_, end, next)) = ws.get(idx + 1) {
+                if is_number(next) {
+                    hits.push(PiiHit { entity: "street_address", start, end });
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn masked(text: &str) -> String {
+        redact(text, &HashMap::new(), "salt").0
+    }
+
+    #[test]
+    fn masks_each_entity_type() {
+        assert_eq!(masked("Mail jane.doe+work@example.co.uk today"), "Mail [EMAIL] today");
+        assert_eq!(masked("Call +44 20 7946 0958."), "Call [PHONE].");
+        assert_eq!(masked("Call (415) 555-0132 or 1-415-555-0199"), "Call [PHONE] or [PHONE]");
+        assert_eq!(masked("Tel. 030 12345678"), "Tel. [PHONE]");
+        assert_eq!(masked("IBAN DE89 3704 0044 0532 0130 00 please"), "IBAN [IBAN] please");
+        assert_eq!(masked("SSN 123-45-6789, NINO AB123456C"), "SSN [NATIONAL_ID], NINO [NATIONAL_ID]");
+        assert_eq!(masked("Ship to 221B Baker Street"), "Ship to [ADDRESS]");
+        assert_eq!(masked("At 1600 Pennsylvania Ave NW"), "At [ADDRESS] NW");
+        assert_eq!(masked("Flat 2, 12 Kings Court, Leeds"), "Flat 2, [ADDRESS], Leeds");
+        assert_eq!(masked("Hauptstraße 5, Berlin"), "[ADDRESS], Berlin");
+    }
+
+    #[test]
+    fn leaves_dates_ids_and_prose_alone() {
+        for text in [
+            "Backup 2024-01-15 103045 finished",
+            "Logged at 15.01.2024 10 30 by cron",
+            "Order 1234-5678-9012 shipped",
+            "Build 20240115.103045.17 passed",
+            "Invoice 4400 1234 5678 is due",
+            "In 2019 the Supreme Court ruled on it",
+            "Since 2019 Supreme Court rulings have changed",
+            "The 10 Best Way to learn is practice",
+            "Chapter 3 Court Of Appeals covers this",
+            "We close at 5 Place Vendome closes later",
+            "Bayern holt Platz 1 in der Tabelle",
+            "Not an IBAN: DE00 1234 5678 9012 3456 78",
+            "Version 3.14.159265 released",
+        ] {
+            assert_eq!(masked(text), text);
+        }
+    }
+
+    #[test]
+    fn hash_mode_gives_the_same_token_for_the_same_value() {
+        let modes = HashMap::from([("email".to_string(), PiiMode::Hash), ("phone".to_string(), PiiMode::Keep)]);
+        let (text, counts) = redact("a@example.com, A@Example.com, b@example.com, 415-555-0132", &modes, "salt");
+        let tokens: Vec<&str> = text.split(", ").collect();
+        assert_eq!(tokens[0], tokens[1]);
+        assert_ne!(tokens[0], tokens[2]);
+        assert!(tokens[0].starts_with("[EMAIL:"));
+        assert_eq!(tokens[3], "415-555-0132");
+        assert_eq!(counts.get("email"), Some(&3));
+        assert_eq!(counts.get("phone"), None);
+    }
+}
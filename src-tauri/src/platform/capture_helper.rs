@@ -0,0 +1,301 @@
+use serde::{Deserialize, Serialize};
+use std::time::{Duration, Instant};
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader, Lines};
+use tokio::process::{Child, ChildStdin, ChildStdout};
+
+// The reattend-capture Swift binary runs as one long-lived child
+// (`reattend-capture serve`) speaking line-delimited JSON-RPC 2.0 over
+// stdin/stdout, instead of a fresh process per capture. Requests are
+// serialized; a request that times out or loses the pipe kills the child,
+// and the next request starts a new one.
+
+/// Protocol spoken by this build; the helper reports its own in `hello`
+const PROTOCOL_VERSION: u64 = 1;
+
+const HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Starts allowed within `RESTART_WINDOW` before requests fail fast
+const MAX_STARTS: usize = 3;
+const RESTART_WINDOW: Duration = Duration::from_secs(60);
+
+/// Version and capabilities reported by the helper's `hello`
+#[derive(Debug, Clone, Deserialize)]
+pub struct HelperInfo {
+    pub version: String,
+    pub protocol: u64,
+    /// Methods this build of the helper supports
+    pub capabilities: Vec<String>,
+}
+
+/// Parameters of the `screenshot` and `capture` methods
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScreenshotParams {
+    /// CGDirectDisplayID; the main display when None
+    pub display: Option<u32>,
+    /// Screen area to capture, cropped to the display (or, without one,
+    /// to the display holding it)
+    pub region: Option<super::Rect>,
+    /// OCR every display
+    pub all_displays: bool,
+    /// Areas to blank out of the screenshot (our own windows)
+    pub exclude: Vec<super::Rect>,
+    /// Vision recognition languages; Vision's defaults when empty (`screenshot` only)
+    pub languages: Vec<String>,
+}
+
+/// A running helper process
+struct Running {
+    // Held so the child is killed when this is dropped
+    child: Child,
+    stdin: ChildStdin,
+    stdout: Lines<BufReader<ChildStdout>>,
+    next_id: u64,
+    info: Option<HelperInfo>,
+}
+
+impl Running {
+    /// Send one request and wait for its response. The outer error means the
+    /// pipe is broken (the process is unusable); the inner one is an error
+    /// the helper answered with.
+    async fn request(
+        &mut self,
+        method: &str,
+        params: serde_json::Value,
+    ) -> Result<Result<serde_json::Value, String>, String> {
+        let id = self.next_id;
+        self.next_id += 1;
+
+        let request = serde_json::json!({ "jsonrpc": "2.0", "id": id, "method": method, "params": params });
+        let mut line = request.to_string();
+        line.push('\n');
+        self.stdin
+            .write_all(line.as_bytes())
+            .await
+            .map_err(|e| format!("Failed to write to capture helper: {}", e))?;
+        self.stdin
+            .flush()
+            .await
+            .map_err(|e| format!("Failed to write to capture helper: {}", e))?;
+
+        loop {
+            let line = self
+                .stdout
+                .next_line()
+                .await
+                .map_err(|e| format!("Failed to read from capture helper: {}", e))?
+                .ok_or_else(|| "Capture helper exited".to_string())?;
+            // Skip stray output and responses to abandoned requests
+            let Ok(response) = serde_json::from_str::<serde_json::Value>(&line) else { continue };
+            if response.get("id").and_then(|v| v.as_u64()) != Some(id) {
+                continue;
+            }
+            if let Some(error) = response.get("error") {
+                let message = error.get("message").and_then(|m| m.as_str()).unwrap_or("Capture helper error");
+                return Ok(Err(message.to_string()));
+            }
+            return Ok(Ok(response.get("result").cloned().unwrap_or(serde_json::Value::Null)));
+        }
+    }
+
+    fn has_exited(&mut self) -> bool {
+        !matches!(self.child.try_wait(), Ok(None))
+    }
+}
+
+#[derive(Default)]
+struct HelperState {
+    running: Option<Running>,
+    /// Recent process starts, for the restart limit
+    starts: Vec<Instant>,
+}
+
+/// Builds the command that starts the helper
+type HelperCommand = Box<dyn Fn() -> Result<tokio::process::Command, String> + Send + Sync>;
+
+/// Client for the long-lived capture helper
+pub struct CaptureHelper {
+    command: HelperCommand,
+    state: tokio::sync::Mutex<HelperState>,
+}
+
+#[cfg(target_os = "macos")]
+static HELPER: std::sync::OnceLock<CaptureHelper> = std::sync::OnceLock::new();
+
+/// The app's capture helper client. The process starts on first use.
+#[cfg(target_os = "macos")]
+pub fn get(app: &tauri::AppHandle) -> &'static CaptureHelper {
+    HELPER.get_or_init(|| {
+        let app = app.clone();
+        CaptureHelper::new(move || {
+            let mut command = tokio::process::Command::new(super::capture_binary_path(&app)?);
+            command.arg("serve");
+            Ok(command)
+        })
+    })
+}
+
+impl CaptureHelper {
+    fn new(command: impl Fn() -> Result<tokio::process::Command, String> + Send + Sync + 'static) -> Self {
+        Self { command: Box::new(command), state: Default::default() }
+    }
+
+    /// Call `method`, starting (or restarting) the helper if needed
+    pub async fn call(
+        &self,
+        method: &str,
+        params: serde_json::Value,
+        timeout: Duration,
+    ) -> Result<serde_json::Value, String> {
+        let mut state = self.state.lock().await;
+
+        // Crashed or killed since the last request
+        if state.running.as_mut().is_some_and(|r| r.has_exited()) {
+            state.running = None;
+        }
+        if state.running.is_none() {
+            state.running = Some(self.start(&mut state.starts).await?);
+        }
+        let Some(running) = state.running.as_mut() else {
+            return Err("Capture helper not running".to_string());
+        };
+
+        if let Some(info) = &running.info {
+            if !info.capabilities.iter().any(|c| c == method) {
+                return Err(format!("reattend-capture {} does not support \"{}\"", info.version, method));
+            }
+        }
+
+        let outcome = tokio::time::timeout(timeout, running.request(method, params)).await;
+        match outcome {
+            Ok(Ok(result)) => result,
+            Ok(Err(e)) => {
+                state.running = None;
+                Err(e)
+            }
+            Err(_) => {
+                // Kill it: it may be stuck, and is still working on this request
+                state.running = None;
+                Err(format!("Capture helper timed out after {}s ({})", timeout.as_secs(), method))
+            }
+        }
+    }
+
+    /// Spawn the helper (`reattend-capture serve`) and check its protocol version
+    async fn start(&self, starts: &mut Vec<Instant>) -> Result<Running, String> {
+        let now = Instant::now();
+        starts.retain(|t| now.duration_since(*t) < RESTART_WINDOW);
+        if starts.len() >= MAX_STARTS {
+            return Err("Capture helper keeps exiting; not restarting for now".to_string());
+        }
+        starts.push(now);
+
+        let mut command = (self.command)()?;
+        let mut child = command
+            .stdin(std::process::Stdio::piped())
+            .stdout(std::process::Stdio::piped())
+            .stderr(std::process::Stdio::inherit())
+            .kill_on_drop(true)
+            .spawn()
+            .map_err(|e| format!("Failed to start capture helper: {}", e))?;
+        let stdin = child.stdin.take().ok_or("Capture helper has no stdin")?;
+        let stdout = child.stdout.take().ok_or("Capture helper has no stdout")?;
+
+        let mut running = Running { child, stdin, stdout: BufReader::new(stdout).lines(), next_id: 1, info: None };
+
+        // Helpers built before `serve` existed exit here with "Unknown command"
+        let hello = tokio::time::timeout(HANDSHAKE_TIMEOUT, running.request("hello", serde_json::json!({})))
+            .await
+            .map_err(|_| "Capture helper handshake timed out".to_string())?
+            .map_err(|e| format!("Capture helper handshake failed ({}). Rebuild the Swift plugin.", e))??;
+        let info: HelperInfo =
+            serde_json::from_value(hello).map_err(|e| format!("Invalid capture helper handshake: {}", e))?;
+        if info.protocol != PROTOCOL_VERSION {
+            return Err(format!(
+                "reattend-capture {} speaks protocol {}, expected {}. Rebuild the Swift plugin.",
+                info.version, info.protocol, PROTOCOL_VERSION
+            ));
+        }
+        running.info = Some(info);
+        Ok(running)
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+
+    /// Stand-in helper: answers `hello` with `protocol`, hangs on "hang",
+    /// exits on "crash" and answers anything else with its pid
+    fn script(protocol: u64) -> CaptureHelper {
+        let script = format!(
+            r#"while IFS= read -r line; do
+  id=$(printf '%s' "$line" | sed 's/.*"id":\([0-9]*\).*/\1/')
+  case "$line" in
+    *'"hello"'*) printf '{{"jsonrpc":"2.0","id":%s,"result":{{"version":"test","protocol":{},"capabilities":["ping","hang","crash"]}}}}\n' "$id" ;;
+    *'"hang"'*) exec sleep 30 ;;
+    *'"crash"'*) exit 1 ;;
+    *) echo "not json"; printf '{{"jsonrpc":"2.0","id":%s,"result":{{"pid":%s}}}}\n' "$id" "$$" ;;
+  esac
+done"#,
+            protocol
+        );
+        CaptureHelper::new(move || {
+            let mut command = tokio::process::Command::new("sh");
+            command.arg("-c").arg(&script);
+            Ok(command)
+        })
+    }
+
+    const TIMEOUT: Duration = Duration::from_secs(5);
+
+    async fn pid(helper: &CaptureHelper) -> u64 {
+        let result = helper.call("ping", serde_json::json!({}), TIMEOUT).await.unwrap();
+        result["pid"].as_u64().expect("pid")
+    }
+
+    #[tokio::test]
+    async fn requests_share_one_process() {
+        let helper = script(PROTOCOL_VERSION);
+        let first = pid(&helper).await;
+        assert_eq!(pid(&helper).await, first);
+        let err = helper.call("screenshot", serde_json::json!({}), TIMEOUT).await.unwrap_err();
+        assert!(err.contains("does not support"), "{}", err);
+        assert_eq!(pid(&helper).await, first);
+    }
+
+    #[tokio::test]
+    async fn hung_request_times_out_and_restarts_the_helper() {
+        let helper = script(PROTOCOL_VERSION);
+        let first = pid(&helper).await;
+        let err = helper
+            .call("hang", serde_json::json!({}), Duration::from_millis(300))
+            .await
+            .unwrap_err();
+        assert!(err.contains("timed out"), "{}", err);
+        assert_ne!(pid(&helper).await, first);
+    }
+
+    #[tokio::test]
+    async fn crashed_helper_is_restarted_until_the_limit() {
+        let helper = script(PROTOCOL_VERSION);
+        let first = pid(&helper).await;
+        let err = helper.call("crash", serde_json::json!({}), TIMEOUT).await.unwrap_err();
+        assert!(err.contains("exited"), "{}", err);
+        assert_ne!(pid(&helper).await, first);
+
+        // Third start crashes too; a fourth within the window is refused
+        assert!(helper.call("crash", serde_json::json!({}), TIMEOUT).await.is_err());
+        let crashes = helper.call("crash", serde_json::json!({}), TIMEOUT).await.unwrap_err();
+        assert!(crashes.contains("exited"), "{}", crashes);
+        let err = helper.call("ping", serde_json::json!({}), TIMEOUT).await.unwrap_err();
+        assert!(err.contains("keeps exiting"), "{}", err);
+    }
+
+    #[tokio::test]
+    async fn protocol_mismatch_is_reported() {
+        let helper = script(PROTOCOL_VERSION + 1);
+        let err = helper.call("ping", serde_json::json!({}), TIMEOUT).await.unwrap_err();
+        assert!(err.contains(&format!("speaks protocol {}", PROTOCOL_VERSION + 1)), "{}", err);
+    }
+}
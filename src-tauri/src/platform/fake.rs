@@ -0,0 +1,215 @@
+use super::{ActiveWindowInfo, OwnWindow, Platform, PlatformFuture, Rect};
+use crate::accessibility::WindowText;
+use crate::clipboard::{ClipboardContent, ClipboardImage};
+use crate::ocr::{CaptureArea, CaptureGeometry, OcrResult};
+use std::collections::VecDeque;
+use std::sync::Mutex;
+
+/// In-memory platform for tests. Scripted with the setters below; every read
+/// returns the scripted state, and OCR results are consumed in order.
+#[derive(Default)]
+pub struct FakePlatform {
+    state: Mutex<FakeState>,
+}
+
+#[derive(Default)]
+struct FakeState {
+    clipboard: Option<ClipboardContent>,
+    /// Text that `simulate_copy` puts on the clipboard
+    selection: Option<String>,
+    copies: usize,
+    window: Option<ActiveWindowInfo>,
+    browser_url: Option<String>,
+    url_lookups: usize,
+    private_window: Option<bool>,
+    window_text: Option<WindowText>,
+    /// Screen OCR results (None: unchanged), each optionally moving focus
+    /// once it is taken. Each reads the window focused when it is taken.
+    screens: VecDeque<(Result<Option<String>, String>, Option<ActiveWindowInfo>)>,
+    image_text: Option<String>,
+    own_windows: Vec<OwnWindow>,
+}
+
+/// Focused window of `app_name` titled `title`
+pub fn window(app_name: &str, title: &str) -> ActiveWindowInfo {
+    ActiveWindowInfo {
+        app_name: app_name.to_string(),
+        title: title.to_string(),
+        app_id: format!("/usr/bin/{}", app_name.to_lowercase().replace(' ', "-")),
+        pid: 4242,
+        x: 120.0,
+        y: 80.0,
+        width: 1280.0,
+        height: 800.0,
+        ..Default::default()
+    }
+}
+
+impl FakePlatform {
+    fn state(&self) -> std::sync::MutexGuard<'_, FakeState> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Select `text` in the focused app; `simulate_copy` will copy it
+    pub fn select(&self, text: &str) {
+        self.state().selection = Some(text.to_string());
+    }
+
+    pub fn set_clipboard(&self, content: ClipboardContent) {
+        self.state().clipboard = Some(content);
+    }
+
+    pub fn focus(&self, app_name: &str, title: &str) {
+        self.state().window = Some(window(app_name, title));
+    }
+
+    pub fn set_browser_url(&self, url: &str) {
+        self.state().browser_url = Some(url.to_string());
+    }
+
+    /// Answer to the browser's private-window query (None: not supported)
+    pub fn set_private_window(&self, private: Option<bool>) {
+        self.state().private_window = private;
+    }
+
+    /// Accessibility text of the focused window (None: not accessible)
+    pub fn set_window_text(&self, text: Option<WindowText>) {
+        self.state().window_text = text;
+    }
+
+    /// Queue the text the next screen OCR returns
+    pub fn push_screen(&self, text: &str) {
+        self.state().screens.push_back((Ok(Some(text.to_string())), None));
+    }
+
+    /// Queue a screen that looks the same as at its last OCR
+    pub fn push_screen_unchanged(&self) {
+        self.state().screens.push_back((Ok(None), None));
+    }
+
+    /// Queue a screen OCR after which focus has moved to another window
+    pub fn push_screen_then_focus(&self, text: &str, app_name: &str, title: &str) {
+        self.state().screens.push_back((Ok(Some(text.to_string())), Some(window(app_name, title))));
+    }
+
+    pub fn push_screen_error(&self, error: &str) {
+        self.state().screens.push_back((Err(error.to_string()), None));
+    }
+
+    /// Show one of Reattend's own windows
+    pub fn show_own_window(&self, label: &str) {
+        let bounds = Rect { x: 1560.0, y: 760.0, width: 340.0, height: 260.0 };
+        self.state().own_windows.push(OwnWindow { label: label.to_string(), bounds });
+    }
+
+    pub fn set_image_text(&self, text: &str) {
+        self.state().image_text = Some(text.to_string());
+    }
+
+    /// Number of simulated copy keystrokes
+    pub fn copies(&self) -> usize {
+        self.state().copies
+    }
+
+    /// Number of browser URL queries (osascript calls on macOS)
+    pub fn url_lookups(&self) -> usize {
+        self.state().url_lookups
+    }
+}
+
+impl Platform for FakePlatform {
+    fn simulate_copy(&self) {
+        let mut state = self.state();
+        state.copies += 1;
+        if let Some(text) = state.selection.clone() {
+            state.clipboard = Some(ClipboardContent::Text(text));
+        }
+    }
+
+    fn read_clipboard(&self) -> Option<String> {
+        match self.state().clipboard {
+            Some(ClipboardContent::Text(ref text)) => Some(text.clone()),
+            Some(ClipboardContent::Html { ref text, .. }) | Some(ClipboardContent::Rtf { ref text, .. }) => text.clone(),
+            Some(ClipboardContent::Image(_)) | None => None,
+        }
+    }
+
+    fn read_clipboard_content(&self) -> Option<ClipboardContent> {
+        self.state().clipboard.clone()
+    }
+
+    fn active_app_name(&self) -> String {
+        self.state()
+            .window
+            .as_ref()
+            .map(|w| w.app_name.clone())
+            .unwrap_or_else(|| "Unknown".to_string())
+    }
+
+    fn active_window(&self) -> Option<ActiveWindowInfo> {
+        self.state().window.clone()
+    }
+
+    fn browser_url<'a>(&'a self, _window: &'a ActiveWindowInfo) -> PlatformFuture<'a, Option<String>> {
+        let mut state = self.state();
+        state.url_lookups += 1;
+        let url = state.browser_url.clone();
+        Box::pin(async move { url })
+    }
+
+    fn is_private_browser_window<'a>(&'a self, _window: &'a ActiveWindowInfo) -> PlatformFuture<'a, Option<bool>> {
+        let private = self.state().private_window;
+        Box::pin(async move { private })
+    }
+
+    fn window_text(&self) -> PlatformFuture<'_, Option<WindowText>> {
+        let text = self.state().window_text.clone();
+        Box::pin(async move { text })
+    }
+
+    fn own_windows(&self) -> Vec<OwnWindow> {
+        self.state().own_windows.clone()
+    }
+
+    fn capture_screen_ocr(&self) -> PlatformFuture<'_, Result<Option<OcrResult>, String>> {
+        let result = {
+            let mut state = self.state();
+            let app_name = state.window.as_ref().map(|w| w.app_name.clone()).unwrap_or_default();
+            let geometry = state
+                .window
+                .as_ref()
+                .map(|w| CaptureGeometry { area: CaptureArea::ActiveWindow, bounds: w.bounds(), display: None });
+            match state.screens.pop_front() {
+                Some((text, focus_after)) => {
+                    if let Some(window) = focus_after {
+                        state.window = Some(window);
+                    }
+                    text.map(|text| {
+                        text.map(|text| OcrResult { text, app_name, confidence: None, provider: "fake", geometry })
+                    })
+                }
+                None => Err("No screen scripted".to_string()),
+            }
+        };
+        Box::pin(async move { result })
+    }
+
+    fn ocr_image<'a>(
+        &'a self,
+        _image: &'a ClipboardImage,
+    ) -> PlatformFuture<'a, Result<OcrResult, String>> {
+        let result = self
+            .state()
+            .image_text
+            .clone()
+            .map(|text| OcrResult {
+                text,
+                app_name: String::new(),
+                confidence: Some(0.9),
+                provider: "fake",
+                geometry: None,
+            })
+            .ok_or_else(|| "No image text scripted".to_string());
+        Box::pin(async move { result })
+    }
+}
@@ -0,0 +1,159 @@
+use tauri_plugin_global_shortcut::Modifiers;
+
+// Linux backend. Everything works on X11. Under Wayland, apps cannot inspect
+// other windows or inject keys, so:
+// - the active window is only known for XWayland apps (otherwise "Unknown"),
+// - selection copy needs `wtype` (wlroots virtual-keyboard),
+// - clipboard falls back to `wl-paste` when XWayland has no copy of it,
+// - screenshots go through xcap's portal/wlr-screencopy backend.
+
+/// True when running in a Wayland session (XWayland may still be available)
+pub(super) fn is_wayland() -> bool {
+    std::env::var_os("WAYLAND_DISPLAY").is_some()
+        || std::env::var("XDG_SESSION_TYPE").map(|t| t == "wayland").unwrap_or(false)
+}
+
+/// Run a helper CLI and return its stdout, if it exists and succeeded
+fn command_output(program: &str, args: &[&str]) -> Option<String> {
+    let output = std::process::Command::new(program).args(args).output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let text = String::from_utf8_lossy(&output.stdout).into_owned();
+    if text.trim().is_empty() { None } else { Some(text) }
+}
+
+/// Run a helper CLI, returning whether it exists and succeeded
+fn command_status(program: &str, args: &[&str]) -> bool {
+    std::process::Command::new(program)
+        .args(args)
+        .status()
+        .map(|s| s.success())
+        .unwrap_or(false)
+}
+
+/// Elevate a window — no-op on Linux (Tauri always_on_top is honoured by X11 WMs and compositors).
+pub fn platform_elevate_window(_window: &tauri::WebviewWindow) {
+    // Tauri's always_on_top(true) is sufficient on Linux
+}
+
+/// Bring the app to the foreground — no-op on Linux (Tauri set_focus handles it;
+/// Wayland compositors may show an attention hint instead of raising).
+pub fn platform_activate_app() {
+    // Tauri's set_focus() handles this on Linux
+}
+
+/// Hide app from the dock/taskbar — no-op on Linux (tray-only apps have no taskbar entry).
+pub fn platform_hide_from_dock() {
+    // Tray-only Tauri apps on Linux have no visible windows at startup
+}
+
+/// Simulate Ctrl+C to copy the current selection.
+/// X11 via enigo (XTest); Wayland via `wtype`, since XTest only reaches XWayland windows.
+pub fn platform_simulate_copy() {
+    if is_wayland() && command_status("wtype", &["-M", "ctrl", "c", "-m", "ctrl"]) {
+        std::thread::sleep(std::time::Duration::from_millis(50));
+        return;
+    }
+    use enigo::{Enigo, Keyboard, Settings, Key, Direction};
+    if let Ok(mut enigo) = Enigo::new(&Settings::default()) {
+        let _ = enigo.key(Key::Control, Direction::Press);
+        let _ = enigo.key(Key::Unicode('c'), Direction::Click);
+        let _ = enigo.key(Key::Control, Direction::Release);
+    }
+    // Small delay for the target app to process
+    std::thread::sleep(std::time::Duration::from_millis(50));
+}
+
+/// Register context menu — no-op on Linux (no desktop-wide Services menu; the
+/// global shortcut is the entry point).
+pub fn platform_register_context_menu() {
+    // No Linux equivalent of macOS Services menu
+}
+
+/// Store app handle — no-op on Linux (no Services callback needed).
+pub fn platform_store_app_handle(_handle: &tauri::AppHandle) {
+    // Not needed on Linux — no Services callback
+}
+
+/// Read clipboard text via arboard (X11/XWayland), falling back to `wl-paste` on Wayland.
+pub fn platform_read_clipboard() -> Option<String> {
+    let text = arboard::Clipboard::new()
+        .ok()
+        .and_then(|mut c| c.get_text().ok())
+        .filter(|t| !t.is_empty());
+    if text.is_none() && is_wayland() {
+        return command_output("wl-paste", &["--no-newline", "--type", "text/plain"]);
+    }
+    text
+}
+
+/// Read the richest clipboard content: HTML, RTF (Wayland only, via `wl-paste`), text or image.
+pub fn platform_read_clipboard_content() -> Option<crate::clipboard::ClipboardContent> {
+    let wayland = is_wayland();
+    let content = crate::clipboard::read_content(|| {
+        if wayland {
+            command_output("wl-paste", &["--no-newline", "--type", "text/rtf"])
+        } else {
+            None
+        }
+    });
+    if content.is_none() && wayland {
+        return platform_read_clipboard().map(crate::clipboard::ClipboardContent::Text);
+    }
+    content
+}
+
+/// Get the name of the currently active/foreground application.
+/// Under Wayland this only works for XWayland windows.
+pub fn platform_get_active_app_name() -> String {
+    match active_win_pos_rs::get_active_window() {
+        Ok(win) => win.app_name,
+        Err(_) => "Unknown".to_string(),
+    }
+}
+
+/// Get the focused window: title, executable path, PID and bounds (X11/XWayland only).
+pub fn platform_get_active_window() -> Option<super::ActiveWindowInfo> {
+    active_win_pos_rs::get_active_window().ok().map(super::ActiveWindowInfo::from)
+}
+
+/// List connected displays.
+pub async fn platform_list_displays(_app_handle: &tauri::AppHandle) -> Result<Vec<super::DisplayInfo>, String> {
+    super::screenshot::list_displays().await
+}
+
+/// Screenshot the area `request` covers, for OCR.
+pub async fn platform_capture_screen(
+    _app_handle: &tauri::AppHandle,
+    request: &crate::ocr::ScreenRequest,
+) -> Result<crate::ocr::ScreenCapture, String> {
+    super::screenshot::capture_screen(request.clone()).await
+}
+
+/// No Linux browser exposes its tab URL; domain rules give way to title rules
+pub const BROWSER_URLS_READABLE: bool = false;
+
+/// Get the URL of the focused browser tab — browsers expose no scripting
+/// interface on Linux, so attribution falls back to the window title.
+pub async fn platform_get_browser_url(_window: &super::ActiveWindowInfo) -> Option<String> {
+    None
+}
+
+/// Browsers expose no private-mode query on Linux; the window title
+/// ("(Incognito)", "Private Browsing") is the only signal.
+pub async fn platform_is_private_browser_window(_window: &super::ActiveWindowInfo) -> Option<bool> {
+    None
+}
+
+/// Return the platform-appropriate shortcut modifier (Ctrl on Linux).
+/// Global shortcuts are grabbed through X11, so on Wayland they only fire
+/// while an XWayland window has focus.
+pub fn platform_shortcut_modifier() -> Modifiers {
+    Modifiers::CONTROL
+}
+
+/// Return the platform shortcut display prefix for menus.
+pub fn platform_shortcut_display() -> &'static str {
+    "Ctrl+Shift+"
+}
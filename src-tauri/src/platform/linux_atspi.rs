@@ -0,0 +1,190 @@
+use crate::accessibility::{self, Node, Role, WindowText};
+use zbus::zvariant::OwnedObjectPath;
+
+// AT-SPI: toolkits (GTK, Qt, Firefox, Chromium with accessibility enabled)
+// export their widget trees on a dedicated accessibility bus, whose address
+// the session bus hands out. Works on X11 and Wayland alike.
+
+/// Reading a large tree is many round trips; give up rather than stall the loop
+const READ_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(3);
+
+/// ATSPI_STATE_ACTIVE / ATSPI_STATE_FOCUSED bit positions in the state set
+const STATE_ACTIVE: u32 = 1;
+const STATE_FOCUSED: u32 = 12;
+
+#[zbus::proxy(interface = "org.a11y.atspi.Accessible", assume_defaults = false)]
+trait Accessible {
+    fn get_children(&self) -> zbus::Result<Vec<(String, OwnedObjectPath)>>;
+    fn get_role_name(&self) -> zbus::Result<String>;
+    fn get_state(&self) -> zbus::Result<Vec<u32>>;
+    fn get_interfaces(&self) -> zbus::Result<Vec<String>>;
+    #[zbus(property)]
+    fn name(&self) -> zbus::Result<String>;
+}
+
+#[zbus::proxy(interface = "org.a11y.atspi.Text", assume_defaults = false)]
+trait Text {
+    fn get_text(&self, start_offset: i32, end_offset: i32) -> zbus::Result<String>;
+}
+
+/// AT-SPI role name → capture role
+fn atspi_role(role: &str) -> Role {
+    match role {
+        "document web" | "document frame" | "document text" | "document email" => Role::Document,
+        "heading" => Role::Heading,
+        "paragraph" | "block quote" | "article" => Role::Paragraph,
+        "label" | "static" | "caption" => Role::Text,
+        "text" | "entry" | "terminal" => Role::Editable,
+        "list item" | "description term" | "description value" => Role::ListItem,
+        "table cell" => Role::Cell,
+        "link" => Role::Link,
+        "menu bar" | "menu" | "menu item" | "tool bar" | "status bar" | "scroll bar" | "push button"
+        | "toggle button" | "check box" | "radio button" | "combo box" | "page tab" | "slider"
+        | "spin button" | "image" | "icon" | "tool tip" | "separator" | "progress bar" => Role::Chrome,
+        // Never read what is typed into a password field
+        "password text" => Role::Chrome,
+        _ => Role::Container,
+    }
+}
+
+fn has_state(states: &[u32], state: u32) -> bool {
+    states
+        .get((state / 32) as usize)
+        .is_some_and(|bits| bits & (1 << (state % 32)) != 0)
+}
+
+/// Connect to the accessibility bus
+async fn connect() -> zbus::Result<zbus::Connection> {
+    let session = zbus::Connection::session().await?;
+    let reply = session
+        .call_method(Some("org.a11y.Bus"), "/org/a11y/bus", Some("org.a11y.Bus"), "GetAddress", &())
+        .await?;
+    let address: String = reply.body().deserialize()?;
+    zbus::connection::Builder::address(address.as_str())?.build().await
+}
+
+/// Proxy for one accessible object. Property caching is off: objects are
+/// read once, and caching would subscribe to change signals for each.
+async fn accessible<'a>(
+    conn: &zbus::Connection,
+    bus: &'a str,
+    path: &'a OwnedObjectPath,
+) -> zbus::Result<AccessibleProxy<'a>> {
+    AccessibleProxy::builder(conn)
+        .destination(bus)?
+        .path(path.as_str())?
+        .cache_properties(zbus::proxy::CacheProperties::No)
+        .build()
+        .await
+}
+
+/// Full text of an object implementing the Text interface
+async fn text_of(conn: &zbus::Connection, bus: &str, path: &OwnedObjectPath) -> zbus::Result<String> {
+    TextProxy::builder(conn)
+        .destination(bus)?
+        .path(path.as_str())?
+        .cache_properties(zbus::proxy::CacheProperties::No)
+        .build()
+        .await?
+        .get_text(0, -1)
+        .await
+}
+
+/// The active top-level window of any application
+async fn active_window(conn: &zbus::Connection) -> zbus::Result<Option<(String, OwnedObjectPath)>> {
+    let registry_path = OwnedObjectPath::try_from("/org/a11y/atspi/accessible/root")?;
+    let registry = accessible(conn, "org.a11y.atspi.Registry", &registry_path).await?;
+    for (bus, app_path) in registry.get_children().await? {
+        let Ok(app) = accessible(conn, &bus, &app_path).await else { continue };
+        let Ok(windows) = app.get_children().await else { continue };
+        for (window_bus, window_path) in windows {
+            let Ok(window) = accessible(conn, &window_bus, &window_path).await else { continue };
+            if has_state(&window.get_state().await.unwrap_or_default(), STATE_ACTIVE) {
+                return Ok(Some((window_bus, window_path)));
+            }
+        }
+    }
+    Ok(None)
+}
+
+/// Flatten the subtree at `root` in document order
+async fn walk(conn: &zbus::Connection, root: (String, OwnedObjectPath)) -> Vec<Node> {
+    let mut nodes = Vec::new();
+    // Depth-first with an explicit stack (async recursion would need boxing)
+    let mut stack = vec![(root, 0usize)];
+    while let Some(((bus, path), depth)) = stack.pop() {
+        if nodes.len() >= accessibility::MAX_NODES {
+            break;
+        }
+        let Ok(proxy) = accessible(conn, &bus, &path).await else { continue };
+        let role = atspi_role(&proxy.get_role_name().await.unwrap_or_default());
+        let states = proxy.get_state().await.unwrap_or_default();
+
+        let mut value = String::new();
+        if role != Role::Chrome
+            && proxy.get_interfaces().await.unwrap_or_default().iter().any(|i| i == "org.a11y.atspi.Text")
+        {
+            value = text_of(conn, &bus, &path).await.unwrap_or_default();
+        }
+
+        nodes.push(Node {
+            depth,
+            role,
+            name: proxy.name().await.unwrap_or_default(),
+            value,
+            focused: has_state(&states, STATE_FOCUSED),
+        });
+
+        if role == Role::Chrome || depth >= accessibility::MAX_DEPTH {
+            continue;
+        }
+        if let Ok(children) = proxy.get_children().await {
+            // Reversed so the first child is popped first
+            stack.extend(children.into_iter().rev().map(|child| (child, depth + 1)));
+        }
+    }
+    nodes
+}
+
+/// Read the focused window's text through AT-SPI. None when the
+/// accessibility bus is unavailable or no window is active.
+pub async fn platform_get_window_text(_app_handle: &tauri::AppHandle) -> Option<WindowText> {
+    tokio::time::timeout(READ_TIMEOUT, async {
+        let conn = connect().await.ok()?;
+        let window = active_window(&conn).await.ok()??;
+        let nodes = walk(&conn, window).await;
+        Some(accessibility::collect(&nodes))
+    })
+    .await
+    .ok()
+    .flatten()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn password_fields_are_skipped() {
+        assert_eq!(atspi_role("password text"), Role::Chrome);
+        assert_eq!(atspi_role("entry"), Role::Editable);
+
+        let node = |depth, role: &str, value: &str| Node {
+            depth,
+            role: atspi_role(role),
+            name: String::new(),
+            value: value.to_string(),
+            focused: false,
+        };
+        let nodes = vec![
+            node(0, "document web", ""),
+            node(1, "paragraph", "Sign in to continue to your account settings."),
+            node(1, "password text", "hunter2hunter2"),
+            node(2, "text", "hunter2hunter2"),
+            node(1, "entry", "alex@example.com"),
+        ];
+        let text = accessibility::collect(&nodes).text;
+        assert!(!text.contains("hunter2"), "{}", text);
+        assert!(text.contains("alex@example.com"), "{}", text);
+    }
+}
@@ -0,0 +1,148 @@
+// Clipboard change notifications on Linux.
+// X11: XFixes SelectionNotify on the CLIPBOARD selection.
+// Wayland: wlr data-control `selection` events (wlroots, KDE, Hyprland, …).
+
+/// Block forever, calling `on_change` once per clipboard change.
+/// Returns an error if neither X11 nor a data-control capable Wayland compositor is reachable.
+pub fn platform_watch_clipboard(on_change: Box<dyn Fn() + Send>) -> Result<(), String> {
+    let on_change: std::rc::Rc<dyn Fn() + Send> = std::rc::Rc::from(on_change);
+    if std::env::var_os("WAYLAND_DISPLAY").is_some() {
+        match wayland::watch(on_change.clone()) {
+            Ok(()) => return Ok(()),
+            // GNOME has no data-control: fall through to XWayland's XFixes events,
+            // which still see copies made in X11 and most XWayland-bridged apps.
+            Err(e) if std::env::var_os("DISPLAY").is_none() => return Err(e),
+            Err(_) => {}
+        }
+    }
+    x11::watch(&*on_change)
+}
+
+mod x11 {
+    use x11rb::connection::Connection;
+    use x11rb::protocol::xfixes::{ConnectionExt as _, SelectionEventMask};
+    use x11rb::protocol::xproto::ConnectionExt as _;
+    use x11rb::protocol::Event;
+
+    pub fn watch(on_change: &dyn Fn()) -> Result<(), String> {
+        let (conn, screen_num) = x11rb::connect(None).map_err(|e| format!("X11 connect failed: {}", e))?;
+        let root = conn.setup().roots[screen_num].root;
+
+        conn.xfixes_query_version(5, 0)
+            .map_err(|e| e.to_string())?
+            .reply()
+            .map_err(|e| format!("XFixes unavailable: {}", e))?;
+        let clipboard = conn
+            .intern_atom(false, b"CLIPBOARD")
+            .map_err(|e| e.to_string())?
+            .reply()
+            .map_err(|e| e.to_string())?
+            .atom;
+
+        conn.xfixes_select_selection_input(
+            root,
+            clipboard,
+            SelectionEventMask::SET_SELECTION_OWNER
+                | SelectionEventMask::SELECTION_WINDOW_DESTROY
+                | SelectionEventMask::SELECTION_CLIENT_CLOSE,
+        )
+        .map_err(|e| e.to_string())?;
+        conn.flush().map_err(|e| e.to_string())?;
+
+        loop {
+            match conn.wait_for_event().map_err(|e| format!("X11 connection lost: {}", e))? {
+                // Only new owners mean new content; destroy/close just empties the clipboard
+                Event::XfixesSelectionNotify(ev)
+                    if ev.subtype == x11rb::protocol::xfixes::SelectionEvent::SET_SELECTION_OWNER =>
+                {
+                    on_change();
+                }
+                _ => {}
+            }
+        }
+    }
+}
+
+mod wayland {
+    use wayland_client::globals::{registry_queue_init, GlobalListContents};
+    use wayland_client::protocol::{wl_registry, wl_seat};
+    use wayland_client::{event_created_child, Connection, Dispatch, Proxy, QueueHandle};
+    use wayland_protocols_wlr::data_control::v1::client::{
+        zwlr_data_control_device_v1::{self, ZwlrDataControlDeviceV1},
+        zwlr_data_control_manager_v1::ZwlrDataControlManagerV1,
+        zwlr_data_control_offer_v1::ZwlrDataControlOfferV1,
+    };
+
+    struct State {
+        on_change: std::rc::Rc<dyn Fn()>,
+        /// The compositor announces the current selection on bind — not a new copy
+        initial_seen: bool,
+        finished: bool,
+    }
+
+    impl Dispatch<wl_registry::WlRegistry, GlobalListContents> for State {
+        fn event(_: &mut Self, _: &wl_registry::WlRegistry, _: wl_registry::Event, _: &GlobalListContents, _: &Connection, _: &QueueHandle<Self>) {}
+    }
+
+    impl Dispatch<wl_seat::WlSeat, ()> for State {
+        fn event(_: &mut Self, _: &wl_seat::WlSeat, _: wl_seat::Event, _: &(), _: &Connection, _: &QueueHandle<Self>) {}
+    }
+
+    impl Dispatch<ZwlrDataControlManagerV1, ()> for State {
+        fn event(_: &mut Self, _: &ZwlrDataControlManagerV1, _: <ZwlrDataControlManagerV1 as Proxy>::Event, _: &(), _: &Connection, _: &QueueHandle<Self>) {}
+    }
+
+    impl Dispatch<ZwlrDataControlOfferV1, ()> for State {
+        fn event(_: &mut Self, _: &ZwlrDataControlOfferV1, _: <ZwlrDataControlOfferV1 as Proxy>::Event, _: &(), _: &Connection, _: &QueueHandle<Self>) {}
+    }
+
+    impl Dispatch<ZwlrDataControlDeviceV1, ()> for State {
+        fn event(
+            state: &mut Self,
+            _: &ZwlrDataControlDeviceV1,
+            event: zwlr_data_control_device_v1::Event,
+            _: &(),
+            _: &Connection,
+            _: &QueueHandle<Self>,
+        ) {
+            match event {
+                zwlr_data_control_device_v1::Event::Selection { id } => {
+                    // We never read through the offer — arboard does that on its own connection
+                    if let Some(offer) = id {
+                        offer.destroy();
+                    }
+                    if state.initial_seen {
+                        (state.on_change)();
+                    }
+                    state.initial_seen = true;
+                }
+                zwlr_data_control_device_v1::Event::Finished => state.finished = true,
+                _ => {}
+            }
+        }
+
+        event_created_child!(State, ZwlrDataControlDeviceV1, [
+            zwlr_data_control_device_v1::EVT_DATA_OFFER_OPCODE => (ZwlrDataControlOfferV1, ()),
+        ]);
+    }
+
+    pub fn watch(on_change: std::rc::Rc<dyn Fn()>) -> Result<(), String> {
+        let conn = Connection::connect_to_env().map_err(|e| format!("Wayland connect failed: {}", e))?;
+        let (globals, mut queue) = registry_queue_init::<State>(&conn).map_err(|e| e.to_string())?;
+        let qh = queue.handle();
+
+        let manager: ZwlrDataControlManagerV1 = globals
+            .bind(&qh, 1..=2, ())
+            .map_err(|_| "Compositor does not support wlr-data-control".to_string())?;
+        let seat: wl_seat::WlSeat = globals
+            .bind(&qh, 1..=7, ())
+            .map_err(|_| "No Wayland seat".to_string())?;
+        let _device = manager.get_data_device(&seat, &qh, ());
+
+        let mut state = State { on_change, initial_seen: false, finished: false };
+        while !state.finished {
+            queue.blocking_dispatch(&mut state).map_err(|e| format!("Wayland dispatch failed: {}", e))?;
+        }
+        Err("Data-control device was destroyed by the compositor".to_string())
+    }
+}
@@ -1,3 +1,5 @@
+use crate::accessibility::{self, Node, Role, WindowText};
+use super::Browser;
 use tauri_plugin_global_shortcut::Modifiers;
 
 // FFI to Objective-C helper — safe wrappers with @try/@catch
@@ -7,6 +9,10 @@ extern "C" {
     fn hide_from_dock();
     fn simulate_copy();
     fn register_services_provider();
+    fn read_clipboard_rtf() -> *mut std::ffi::c_char;
+    fn free_clipboard_string(ptr: *mut std::ffi::c_char);
+    fn bundle_id_for_pid(pid: std::ffi::c_int) -> *mut std::ffi::c_char;
+    fn pasteboard_change_count() -> std::ffi::c_long;
 }
 
 /// Global app handle for the macOS Services callback
@@ -70,6 +76,35 @@ pub fn platform_read_clipboard() -> Option<String> {
     if text.is_empty() { None } else { Some(text) }
 }
 
+/// Read the richest clipboard content: HTML, RTF (via NSPasteboard), text or image.
+pub fn platform_read_clipboard_content() -> Option<crate::clipboard::ClipboardContent> {
+    crate::clipboard::read_content(|| unsafe {
+        let ptr = read_clipboard_rtf();
+        if ptr.is_null() { return None; }
+        let rtf = std::ffi::CStr::from_ptr(ptr).to_string_lossy().into_owned();
+        free_clipboard_string(ptr);
+        Some(rtf)
+    })
+}
+
+/// Block forever, calling `on_change` once per clipboard change.
+/// macOS has no pasteboard notification, so this watches NSPasteboard's change
+/// counter — a cheap in-process read, unlike fetching the clipboard contents.
+pub fn platform_watch_clipboard(on_change: Box<dyn Fn() + Send>) -> Result<(), String> {
+    let mut last = unsafe { pasteboard_change_count() };
+    if last < 0 {
+        return Err("NSPasteboard unavailable".to_string());
+    }
+    loop {
+        std::thread::sleep(std::time::Duration::from_millis(200));
+        let count = unsafe { pasteboard_change_count() };
+        if count >= 0 && count != last {
+            last = count;
+            on_change();
+        }
+    }
+}
+
 /// Get the name of the currently active/foreground application.
 pub fn platform_get_active_app_name() -> String {
     match active_win_pos_rs::get_active_window() {
@@ -78,8 +113,103 @@ pub fn platform_get_active_app_name() -> String {
     }
 }
 
-/// Capture the screen and perform OCR using the Swift Vision binary.
-pub async fn platform_capture_screen_ocr(app_handle: &tauri::AppHandle) -> Result<serde_json::Value, String> {
+/// Get the focused window: title, bundle id, PID and bounds.
+pub fn platform_get_active_window() -> Option<super::ActiveWindowInfo> {
+    let win = active_win_pos_rs::get_active_window().ok()?;
+    let mut info = super::ActiveWindowInfo::from(win);
+    let bundle_id = unsafe {
+        let ptr = bundle_id_for_pid(info.pid as std::ffi::c_int);
+        if ptr.is_null() {
+            None
+        } else {
+            let id = std::ffi::CStr::from_ptr(ptr).to_string_lossy().into_owned();
+            free_clipboard_string(ptr);
+            Some(id)
+        }
+    };
+    if let Some(id) = bundle_id {
+        info.app_id = id;
+    }
+    Some(info)
+}
+
+/// AppleScript reference to the window's app by bundle id. App names come
+/// from the window server and are never spliced into a script; apps without
+/// a plain bundle id are not scripted at all.
+fn script_target(window: &super::ActiveWindowInfo) -> Option<String> {
+    let id = &window.app_id;
+    let plain = id.contains('.') && id.chars().all(|c| c.is_ascii_alphanumeric() || c == '.' || c == '-');
+    if plain { Some(format!("application id \"{}\"", id)) } else { None }
+}
+
+/// AppleScript that returns the front tab's URL, for browsers that support it.
+/// Firefox has no AppleScript dictionary for tabs, so it is not covered.
+fn browser_url_script(window: &super::ActiveWindowInfo) -> Option<String> {
+    let browser = super::browser(&window.app_name)?;
+    let target = script_target(window)?;
+    match browser {
+        Browser::Safari => Some(format!("tell {} to get URL of front document", target)),
+        Browser::Firefox => None,
+        _ => Some(format!("tell {} to get URL of active tab of front window", target)),
+    }
+}
+
+/// Browser tab URLs are read over AppleScript (Firefox and apps without
+/// Automation permission still report none)
+pub const BROWSER_URLS_READABLE: bool = true;
+
+/// Get the URL of the focused browser tab via AppleScript (needs Automation permission).
+pub async fn platform_get_browser_url(window: &super::ActiveWindowInfo) -> Option<String> {
+    let script = browser_url_script(window)?;
+    let output = tokio::time::timeout(
+        std::time::Duration::from_millis(1500),
+        tokio::process::Command::new("osascript").arg("-e").arg(&script).output(),
+    )
+    .await
+    .ok()?
+    .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let url = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    if url.is_empty() || url == "missing value" { None } else { Some(url) }
+}
+
+/// AppleScript that reports whether the front window is private.
+/// Chromium browsers expose `mode` ("normal"/"incognito"); Arc exposes `incognito`.
+/// Safari and Firefox have no such property: Firefox is judged by its window
+/// title, and every Safari window is treated as private (see `privacy`).
+fn private_window_script(window: &super::ActiveWindowInfo) -> Option<String> {
+    let browser = super::browser(&window.app_name)?;
+    let target = script_target(window)?;
+    match browser {
+        Browser::Arc => Some(format!("tell {} to get incognito of front window", target)),
+        Browser::Chrome | Browser::Chromium | Browser::Brave | Browser::Edge => {
+            Some(format!("tell {} to get mode of front window", target))
+        }
+        _ => None,
+    }
+}
+
+/// Ask the browser whether its front window is private/incognito (needs Automation permission).
+pub async fn platform_is_private_browser_window(window: &super::ActiveWindowInfo) -> Option<bool> {
+    let script = private_window_script(window)?;
+    let output = tokio::time::timeout(
+        std::time::Duration::from_millis(1500),
+        tokio::process::Command::new("osascript").arg("-e").arg(&script).output(),
+    )
+    .await
+    .ok()?
+    .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let answer = String::from_utf8_lossy(&output.stdout).trim().to_lowercase();
+    Some(answer == "incognito" || answer == "true")
+}
+
+/// Locate the reattend-capture Swift binary (bundled resource, or dev build).
+pub fn capture_binary_path(app_handle: &tauri::AppHandle) -> Result<std::path::PathBuf, String> {
     use tauri::Manager;
 
     let resource_dir = app_handle
@@ -99,27 +229,103 @@ pub async fn platform_capture_screen_ocr(app_handle: &tauri::AppHandle) -> Resul
     .join("debug")
     .join("reattend-capture");
 
-    let bin_path = if bundled_bin.exists() {
-        bundled_bin
+    if bundled_bin.exists() {
+        Ok(bundled_bin)
     } else if dev_bin.exists() {
-        dev_bin
+        Ok(dev_bin)
     } else {
-        return Err("reattend-capture binary not found. Build the Swift plugin first.".to_string());
+        Err("reattend-capture binary not found. Build the Swift plugin first.".to_string())
+    }
+}
+
+/// List connected displays with the Swift helper.
+pub async fn platform_list_displays(app_handle: &tauri::AppHandle) -> Result<Vec<super::DisplayInfo>, String> {
+    let value = super::capture_helper::get(app_handle)
+        .call("displays", serde_json::json!({}), std::time::Duration::from_secs(2))
+        .await?;
+    serde_json::from_value(value).map_err(|e| format!("Invalid display list: {}", e))
+}
+
+/// Screenshot the area `request` covers with the Swift helper, for OCR. The
+/// helper keeps the pixels until the next capture; providers refer to them
+/// by id.
+pub async fn platform_capture_screen(
+    app_handle: &tauri::AppHandle,
+    request: &crate::ocr::ScreenRequest,
+) -> Result<crate::ocr::ScreenCapture, String> {
+    use crate::ocr::{CaptureArea, CaptureGeometry, ScreenCapture};
+    use super::capture_helper::ScreenshotParams;
+
+    let display = request.display.as_ref().map(|d| d.id);
+    let exclude = request.exclude.clone();
+    let params = match request.area {
+        CaptureArea::ActiveWindow => ScreenshotParams { display, region: request.window, exclude, ..Default::default() },
+        CaptureArea::ActiveMonitor | CaptureArea::RotateMonitors => {
+            ScreenshotParams { display, exclude, ..Default::default() }
+        }
+        CaptureArea::AllMonitors => ScreenshotParams { all_displays: true, exclude, ..Default::default() },
     };
+    let value = super::capture_helper::get(app_handle)
+        .call("capture", serde_json::json!(params), std::time::Duration::from_secs(10))
+        .await?;
 
-    let output = tokio::process::Command::new(&bin_path)
-        .arg("screenshot")
-        .output()
-        .await
-        .map_err(|e| format!("Failed to run capture: {}", e))?;
+    let id = value["id"].as_u64().ok_or("Capture without an id")?;
+    let bounds: super::Rect =
+        serde_json::from_value(value["bounds"].clone()).map_err(|e| format!("Invalid capture bounds: {}", e))?;
+    let fingerprint = crate::screen_change::Fingerprint::from_base64(value["fingerprint"].as_str().unwrap_or(""), bounds)
+        .ok_or("Invalid capture fingerprint")?;
+    Ok(ScreenCapture {
+        app_name: value["appName"].as_str().unwrap_or("").to_string(),
+        geometry: CaptureGeometry { area: request.area, bounds, display: request.display.clone() },
+        fingerprint,
+        id,
+    })
+}
 
-    if !output.status.success() {
-        let stderr = String::from_utf8_lossy(&output.stderr);
-        return Err(format!("Capture failed: {}", stderr));
+/// macOS accessibility role → capture role
+fn ax_role(role: &str) -> Role {
+    match role {
+        "AXWebArea" => Role::Document,
+        "AXHeading" => Role::Heading,
+        "AXStaticText" => Role::Text,
+        "AXTextArea" | "AXTextField" | "AXComboBox" | "AXSearchField" => Role::Editable,
+        "AXLink" => Role::Link,
+        "AXCell" => Role::Cell,
+        "AXToolbar" | "AXMenuBar" | "AXMenuBarItem" | "AXMenu" | "AXMenuItem" | "AXMenuButton"
+        | "AXButton" | "AXPopUpButton" | "AXCheckBox" | "AXRadioButton" | "AXScrollBar"
+        | "AXSlider" | "AXIncrementor" | "AXImage" | "AXRuler" | "AXSplitter" => Role::Chrome,
+        _ => Role::Container,
     }
+}
 
-    let stdout = String::from_utf8_lossy(&output.stdout);
-    serde_json::from_str(&stdout).map_err(|e| format!("Invalid JSON: {}", e))
+/// Read the focused window's accessibility tree with the Swift helper
+/// (needs Accessibility permission).
+pub async fn platform_get_window_text(app_handle: &tauri::AppHandle) -> Option<WindowText> {
+    #[derive(serde::Deserialize)]
+    struct AxNode {
+        depth: usize,
+        role: String,
+        name: String,
+        value: String,
+        focused: bool,
+    }
+
+    let params = serde_json::json!({
+        "maxNodes": accessibility::MAX_NODES,
+        "maxDepth": accessibility::MAX_DEPTH,
+        // The helper returns what it has read by then, before the call times out
+        "timeLimitMs": 2500,
+    });
+    let value = super::capture_helper::get(app_handle)
+        .call("ax_text", params, std::time::Duration::from_secs(4))
+        .await
+        .ok()?;
+    let nodes: Vec<AxNode> = serde_json::from_value(value).ok()?;
+    let nodes: Vec<Node> = nodes
+        .into_iter()
+        .map(|n| Node { depth: n.depth, role: ax_role(&n.role), name: n.name, value: n.value, focused: n.focused })
+        .collect();
+    Some(accessibility::collect(&nodes))
 }
 
 /// Return the platform-appropriate shortcut modifier (Cmd on macOS).
@@ -2,8 +2,367 @@
 mod macos;
 #[cfg(target_os = "macos")]
 pub use macos::*;
+// Long-lived reattend-capture process that macOS capture goes through
+// (tested on any unix, with a shell script standing in for the helper)
+#[cfg(any(target_os = "macos", all(test, unix)))]
+#[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+pub mod capture_helper;
 
 #[cfg(target_os = "windows")]
 mod windows;
 #[cfg(target_os = "windows")]
 pub use windows::*;
+
+#[cfg(target_os = "linux")]
+mod linux;
+#[cfg(target_os = "linux")]
+mod linux_atspi;
+#[cfg(target_os = "linux")]
+mod linux_clipboard;
+#[cfg(target_os = "linux")]
+pub use linux::*;
+#[cfg(target_os = "linux")]
+pub use linux_atspi::platform_get_window_text;
+#[cfg(target_os = "linux")]
+pub use linux_clipboard::platform_watch_clipboard;
+
+// Screenshots for the OCR providers (macOS captures in the Swift helper)
+#[cfg(any(target_os = "windows", target_os = "linux"))]
+pub mod screenshot;
+
+// Scriptable platform for tests
+#[cfg(test)]
+pub mod fake;
+
+/// Snapshot of the focused window, used to attribute captures to their source.
+#[derive(Debug, Clone, Default, serde::Serialize)]
+pub struct ActiveWindowInfo {
+    pub app_name: String,
+    pub title: String,
+    /// Bundle identifier on macOS, executable path elsewhere
+    pub app_id: String,
+    pub pid: u64,
+    pub window_id: String,
+    /// Window bounds in screen coordinates
+    pub x: f64,
+    pub y: f64,
+    pub width: f64,
+    pub height: f64,
+}
+
+impl ActiveWindowInfo {
+    pub fn bounds(&self) -> Rect {
+        Rect { x: self.x, y: self.y, width: self.width, height: self.height }
+    }
+}
+
+/// Web browsers known by app name, for URLs, private windows and domain rules
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Browser {
+    Chrome,
+    Chromium,
+    Safari,
+    Firefox,
+    Brave,
+    Edge,
+    Opera,
+    Vivaldi,
+    Arc,
+}
+
+/// Lowercase app-name substrings of each browser
+const BROWSERS: &[(&str, Browser)] = &[
+    ("chrome", Browser::Chrome),
+    ("chromium", Browser::Chromium),
+    ("safari", Browser::Safari),
+    ("firefox", Browser::Firefox),
+    ("brave", Browser::Brave),
+    ("edge", Browser::Edge),
+    ("opera", Browser::Opera),
+    ("vivaldi", Browser::Vivaldi),
+];
+
+/// The browser an app is, if any
+pub fn browser(app_name: &str) -> Option<Browser> {
+    let lower = app_name.to_lowercase();
+    // Arc is matched by exact name — "arc" is a substring of too many app names
+    if lower == "arc" {
+        return Some(Browser::Arc);
+    }
+    BROWSERS.iter().find(|(name, _)| lower.contains(name)).map(|(_, browser)| *browser)
+}
+
+/// Rectangle in screen coordinates: points from the main display's top-left
+/// corner on macOS, pixels from the primary monitor's elsewhere
+#[derive(Debug, Clone, Copy, PartialEq, Default, serde::Serialize, serde::Deserialize)]
+pub struct Rect {
+    pub x: f64,
+    pub y: f64,
+    pub width: f64,
+    pub height: f64,
+}
+
+impl Rect {
+    pub fn is_empty(&self) -> bool {
+        self.width <= 0.0 || self.height <= 0.0
+    }
+
+    pub fn center(&self) -> (f64, f64) {
+        (self.x + self.width / 2.0, self.y + self.height / 2.0)
+    }
+
+    /// Overlap of the two rectangles, None if they don't overlap
+    pub fn intersect(&self, other: &Rect) -> Option<Rect> {
+        let x = self.x.max(other.x);
+        let y = self.y.max(other.y);
+        let right = (self.x + self.width).min(other.x + other.width);
+        let bottom = (self.y + self.height).min(other.y + other.height);
+        let rect = Rect { x, y, width: right - x, height: bottom - y };
+        (!rect.is_empty()).then_some(rect)
+    }
+
+    /// Smallest rectangle containing both
+    pub fn union(&self, other: &Rect) -> Rect {
+        let x = self.x.min(other.x);
+        let y = self.y.min(other.y);
+        let right = (self.x + self.width).max(other.x + other.width);
+        let bottom = (self.y + self.height).max(other.y + other.height);
+        Rect { x, y, width: right - x, height: bottom - y }
+    }
+}
+
+/// A connected display
+#[derive(Debug, Clone, PartialEq, Default, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DisplayInfo {
+    /// CGDirectDisplayID on macOS, the xcap monitor id elsewhere
+    pub id: u32,
+    pub name: String,
+    pub bounds: Rect,
+    pub scale_factor: f64,
+    pub primary: bool,
+}
+
+/// The display showing most of `window`, else the primary display
+pub fn display_for(displays: &[DisplayInfo], window: Option<Rect>) -> Option<&DisplayInfo> {
+    let overlap = |display: &DisplayInfo| {
+        window
+            .and_then(|w| w.intersect(&display.bounds))
+            .map_or(0.0, |r| r.width * r.height)
+    };
+    displays
+        .iter()
+        .filter(|d| overlap(d) > 0.0)
+        .max_by(|a, b| overlap(a).total_cmp(&overlap(b)))
+        .or_else(|| displays.iter().find(|d| d.primary))
+        .or_else(|| displays.first())
+}
+
+/// Label of the ambient popup, the one window of ours shown over other apps
+/// while they are in use
+pub const AMBIENT_WINDOW: &str = "ambient";
+
+/// One of Reattend's own windows on screen
+#[derive(Debug, Clone, PartialEq)]
+pub struct OwnWindow {
+    pub label: String,
+    pub bounds: Rect,
+}
+
+impl OwnWindow {
+    /// The ambient popup floats over whatever is being read, so it is masked
+    /// out of screenshots. Any other window of ours (Quick Capture, Ask AI,
+    /// Settings) means the user is working in Reattend, and nothing is read.
+    pub fn is_overlay(&self) -> bool {
+        self.label == AMBIENT_WINDOW
+    }
+}
+
+/// Reattend's visible windows, in the coordinates screenshots use
+pub fn own_windows(app: &tauri::AppHandle) -> Vec<OwnWindow> {
+    use tauri::Manager;
+    app.webview_windows()
+        .into_iter()
+        .filter(|(_, w)| w.is_visible().unwrap_or(false) && !w.is_minimized().unwrap_or(false))
+        .filter_map(|(label, w)| {
+            let position = w.outer_position().ok()?;
+            let size = w.outer_size().ok()?;
+            let bounds = Rect {
+                x: position.x as f64,
+                y: position.y as f64,
+                width: size.width as f64,
+                height: size.height as f64,
+            };
+            // macOS screenshots are laid out in points
+            #[cfg(target_os = "macos")]
+            let bounds = {
+                let scale = w.scale_factor().unwrap_or(1.0);
+                Rect { x: bounds.x / scale, y: bounds.y / scale, width: bounds.width / scale, height: bounds.height / scale }
+            };
+            Some(OwnWindow { label, bounds })
+        })
+        .collect()
+}
+
+impl From<active_win_pos_rs::ActiveWindow> for ActiveWindowInfo {
+    fn from(win: active_win_pos_rs::ActiveWindow) -> Self {
+        Self {
+            app_name: win.app_name,
+            title: win.title,
+            app_id: win.process_path.to_string_lossy().into_owned(),
+            pid: win.process_id,
+            window_id: win.window_id,
+            x: win.position.x,
+            y: win.position.y,
+            width: win.position.width,
+            height: win.position.height,
+        }
+    }
+}
+
+/// Boxed future returned by the async `Platform` methods (keeps the trait object-safe)
+pub type PlatformFuture<'a, T> = std::pin::Pin<Box<dyn std::future::Future<Output = T> + Send + 'a>>;
+
+/// What the capture paths read from the OS: clipboard, focused window, browser
+/// state, accessibility text and OCR. Stored in Tauri state as a `SharedPlatform` so that capture
+/// logic can run against `fake::FakePlatform` in tests.
+///
+/// Window management and startup hooks (elevate, dock, Services menu,
+/// shortcuts, clipboard watching) stay free functions — they act on real
+/// Tauri windows and have nothing to fake.
+pub trait Platform: Send + Sync {
+    /// Simulate Cmd/Ctrl+C in the focused app
+    fn simulate_copy(&self);
+    fn read_clipboard(&self) -> Option<String>;
+    fn read_clipboard_content(&self) -> Option<crate::clipboard::ClipboardContent>;
+    fn active_app_name(&self) -> String;
+    fn active_window(&self) -> Option<ActiveWindowInfo>;
+    fn browser_url<'a>(&'a self, window: &'a ActiveWindowInfo) -> PlatformFuture<'a, Option<String>>;
+    fn is_private_browser_window<'a>(&'a self, window: &'a ActiveWindowInfo) -> PlatformFuture<'a, Option<bool>>;
+    /// Text of the focused window from its accessibility tree, if the app exposes one
+    fn window_text(&self) -> PlatformFuture<'_, Option<crate::accessibility::WindowText>>;
+    /// Reattend's own visible windows
+    fn own_windows(&self) -> Vec<OwnWindow>;
+    /// OCR of the screen; None when it hasn't changed since its last OCR
+    fn capture_screen_ocr(&self) -> PlatformFuture<'_, Result<Option<crate::ocr::OcrResult>, String>>;
+    fn ocr_image<'a>(
+        &'a self,
+        image: &'a crate::clipboard::ClipboardImage,
+    ) -> PlatformFuture<'a, Result<crate::ocr::OcrResult, String>>;
+}
+
+pub type SharedPlatform = std::sync::Arc<dyn Platform>;
+
+/// The platform registered in Tauri state at startup
+pub fn get(app: &tauri::AppHandle) -> SharedPlatform {
+    use tauri::Manager;
+    app.state::<SharedPlatform>().inner().clone()
+}
+
+/// The real OS, backed by the per-OS `platform_*` functions and the
+/// configured OCR providers (which need the app handle for their settings,
+/// as does the macOS helper binary).
+pub struct NativePlatform {
+    app: tauri::AppHandle,
+}
+
+impl NativePlatform {
+    pub fn new(app: tauri::AppHandle) -> Self {
+        Self { app }
+    }
+}
+
+impl Platform for NativePlatform {
+    fn simulate_copy(&self) {
+        platform_simulate_copy()
+    }
+
+    fn read_clipboard(&self) -> Option<String> {
+        platform_read_clipboard()
+    }
+
+    fn read_clipboard_content(&self) -> Option<crate::clipboard::ClipboardContent> {
+        platform_read_clipboard_content()
+    }
+
+    fn active_app_name(&self) -> String {
+        platform_get_active_app_name()
+    }
+
+    fn active_window(&self) -> Option<ActiveWindowInfo> {
+        platform_get_active_window()
+    }
+
+    fn browser_url<'a>(&'a self, window: &'a ActiveWindowInfo) -> PlatformFuture<'a, Option<String>> {
+        Box::pin(platform_get_browser_url(window))
+    }
+
+    fn is_private_browser_window<'a>(&'a self, window: &'a ActiveWindowInfo) -> PlatformFuture<'a, Option<bool>> {
+        Box::pin(platform_is_private_browser_window(window))
+    }
+
+    fn window_text(&self) -> PlatformFuture<'_, Option<crate::accessibility::WindowText>> {
+        Box::pin(platform_get_window_text(&self.app))
+    }
+
+    fn own_windows(&self) -> Vec<OwnWindow> {
+        own_windows(&self.app)
+    }
+
+    fn capture_screen_ocr(&self) -> PlatformFuture<'_, Result<Option<crate::ocr::OcrResult>, String>> {
+        Box::pin(crate::ocr::recognize_screen(&self.app))
+    }
+
+    fn ocr_image<'a>(
+        &'a self,
+        image: &'a crate::clipboard::ClipboardImage,
+    ) -> PlatformFuture<'a, Result<crate::ocr::OcrResult, String>> {
+        Box::pin(async move {
+            let png = image.to_png()?;
+            crate::ocr::recognize_image(&self.app, &png).await
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn display(id: u32, x: f64, primary: bool) -> DisplayInfo {
+        DisplayInfo {
+            id,
+            name: format!("Display {}", id),
+            bounds: Rect { x, y: 0.0, width: 1920.0, height: 1080.0 },
+            scale_factor: 1.0,
+            primary,
+        }
+    }
+
+    #[test]
+    fn browsers_are_known_by_app_name() {
+        assert_eq!(browser("Google Chrome"), Some(Browser::Chrome));
+        assert_eq!(browser("Chromium"), Some(Browser::Chromium));
+        assert_eq!(browser("Microsoft Edge"), Some(Browser::Edge));
+        assert_eq!(browser("firefox.exe"), Some(Browser::Firefox));
+        assert_eq!(browser("Arc"), Some(Browser::Arc));
+        assert_eq!(browser("Archive Utility"), None);
+        assert_eq!(browser("Notes"), None);
+    }
+
+    #[test]
+    fn window_belongs_to_the_display_showing_most_of_it() {
+        let displays = vec![display(1, -1920.0, false), display(2, 0.0, true), display(3, 1920.0, false)];
+        // Straddles displays 2 and 3, mostly on 3
+        let window = Rect { x: 1800.0, y: 100.0, width: 800.0, height: 600.0 };
+        assert_eq!(display_for(&displays, Some(window)).map(|d| d.id), Some(3));
+
+        let left = Rect { x: -1000.0, ..window };
+        assert_eq!(display_for(&displays, Some(left)).map(|d| d.id), Some(1));
+
+        // Off-screen or unknown windows go to the primary display
+        let off_screen = Rect { x: 9000.0, ..window };
+        assert_eq!(display_for(&displays, Some(off_screen)).map(|d| d.id), Some(2));
+        assert_eq!(display_for(&displays, None).map(|d| d.id), Some(2));
+        assert!(display_for(&[], Some(window)).is_none());
+    }
+}
@@ -0,0 +1,240 @@
+// Screenshots for the Windows and Linux backends (macOS captures the screen
+// in the Swift helper).
+
+use super::{DisplayInfo, Rect};
+use crate::ocr::{CaptureArea, CaptureGeometry, ScreenCapture, ScreenRequest};
+use crate::screen_change::Fingerprint;
+use image::RgbaImage;
+
+fn monitor_error(e: xcap::XCapError) -> String {
+    format!("Monitor error: {}", e)
+}
+
+fn capture_error(e: xcap::XCapError) -> String {
+    #[cfg(target_os = "linux")]
+    if super::linux::is_wayland() {
+        return format!("Capture error: {} (Wayland: needs xdg-desktop-portal or wlr-screencopy)", e);
+    }
+    format!("Capture error: {}", e)
+}
+
+fn monitor_bounds(monitor: &xcap::Monitor) -> Result<Rect, String> {
+    Ok(Rect {
+        x: monitor.x().map_err(monitor_error)? as f64,
+        y: monitor.y().map_err(monitor_error)? as f64,
+        width: monitor.width().map_err(monitor_error)? as f64,
+        height: monitor.height().map_err(monitor_error)? as f64,
+    })
+}
+
+fn display_info(monitor: &xcap::Monitor) -> Result<DisplayInfo, String> {
+    Ok(DisplayInfo {
+        id: monitor.id().map_err(monitor_error)?,
+        name: monitor.name().map_err(monitor_error)?,
+        bounds: monitor_bounds(monitor)?,
+        scale_factor: monitor.scale_factor().map_err(monitor_error)? as f64,
+        primary: monitor.is_primary().unwrap_or(false),
+    })
+}
+
+/// Connected monitors.
+/// Blocking — call from `spawn_blocking` (xcap types are !Send).
+fn displays() -> Result<Vec<DisplayInfo>, String> {
+    xcap::Monitor::all().map_err(monitor_error)?.iter().map(display_info).collect()
+}
+
+/// Connected monitors, listed off the async runtime
+pub async fn list_displays() -> Result<Vec<DisplayInfo>, String> {
+    tokio::task::spawn_blocking(displays)
+        .await
+        .map_err(|e| format!("Display task panicked: {}", e))?
+}
+
+/// The monitor with id `display`, else the one holding the center of
+/// `window`, else the primary monitor
+fn monitor_for(display: Option<u32>, window: Option<Rect>) -> Result<xcap::Monitor, String> {
+    if let Some(id) = display {
+        let monitors = xcap::Monitor::all().map_err(monitor_error)?;
+        if let Some(monitor) = monitors.into_iter().find(|m| m.id().ok() == Some(id)) {
+            return Ok(monitor);
+        }
+    }
+    if let Some((x, y)) = window.map(|w| w.center()) {
+        if let Ok(monitor) = xcap::Monitor::from_point(x as i32, y as i32) {
+            return Ok(monitor);
+        }
+    }
+    let monitors = xcap::Monitor::all().map_err(monitor_error)?;
+    let primary = monitors.iter().position(|m| m.is_primary().unwrap_or(false)).unwrap_or(0);
+    monitors.into_iter().nth(primary).ok_or_else(|| "No monitor found".to_string())
+}
+
+/// Cut `rect` (screen coordinates) out of a capture of the monitor at
+/// `monitor`. HiDPI captures have more pixels than their bounds, so
+/// coordinates are scaled. None when `rect` is off this monitor.
+fn crop(image: &RgbaImage, monitor: Rect, rect: Rect) -> Option<(RgbaImage, Rect)> {
+    let rect = rect.intersect(&monitor)?;
+    let scale_x = image.width() as f64 / monitor.width;
+    let scale_y = image.height() as f64 / monitor.height;
+    let x = (((rect.x - monitor.x) * scale_x).round() as u32).min(image.width());
+    let y = (((rect.y - monitor.y) * scale_y).round() as u32).min(image.height());
+    let width = ((rect.width * scale_x).round() as u32).min(image.width() - x);
+    let height = ((rect.height * scale_y).round() as u32).min(image.height() - y);
+    if width == 0 || height == 0 {
+        return None;
+    }
+    Some((image::imageops::crop_imm(image, x, y, width, height).to_image(), rect))
+}
+
+/// Lay monitor captures out as the monitors are arranged, at the highest
+/// pixel density among them
+fn stitch(shots: Vec<(Rect, RgbaImage)>) -> Option<(RgbaImage, Rect)> {
+    let bounds = shots.iter().map(|(b, _)| *b).reduce(|a, b| a.union(&b))?;
+    let scale = shots
+        .iter()
+        .map(|(b, image)| image.width() as f64 / b.width)
+        .fold(1.0_f64, f64::max);
+    let mut canvas = RgbaImage::new(
+        (bounds.width * scale).round() as u32,
+        (bounds.height * scale).round() as u32,
+    );
+    for (b, image) in shots {
+        let width = (b.width * scale).round() as u32;
+        let height = (b.height * scale).round() as u32;
+        let image = if image.dimensions() == (width, height) {
+            image
+        } else {
+            image::imageops::resize(&image, width, height, image::imageops::FilterType::Triangle)
+        };
+        let x = ((b.x - bounds.x) * scale).round() as i64;
+        let y = ((b.y - bounds.y) * scale).round() as i64;
+        image::imageops::overlay(&mut canvas, &image, x, y);
+    }
+    Some((canvas, bounds))
+}
+
+/// Black out the parts of `rects` (screen coordinates) that fall inside a
+/// capture of `bounds`
+fn mask(image: &mut RgbaImage, bounds: Rect, rects: &[Rect]) {
+    let scale_x = image.width() as f64 / bounds.width;
+    let scale_y = image.height() as f64 / bounds.height;
+    for rect in rects.iter().filter_map(|r| r.intersect(&bounds)) {
+        let left = (((rect.x - bounds.x) * scale_x).floor() as u32).min(image.width());
+        let top = (((rect.y - bounds.y) * scale_y).floor() as u32).min(image.height());
+        let right = (((rect.x + rect.width - bounds.x) * scale_x).ceil() as u32).min(image.width());
+        let bottom = (((rect.y + rect.height - bounds.y) * scale_y).ceil() as u32).min(image.height());
+        for y in top..bottom {
+            for x in left..right {
+                image.put_pixel(x, y, image::Rgba([0, 0, 0, 255]));
+            }
+        }
+    }
+}
+
+/// Capture the area `request` covers, with our own windows masked out.
+/// Blocking — call from `spawn_blocking` (xcap types are !Send).
+pub fn capture(request: ScreenRequest) -> Result<(RgbaImage, CaptureGeometry), String> {
+    let (mut image, geometry) = capture_unmasked(&request)?;
+    mask(&mut image, geometry.bounds, &request.exclude);
+    Ok((image, geometry))
+}
+
+fn capture_unmasked(request: &ScreenRequest) -> Result<(RgbaImage, CaptureGeometry), String> {
+    if request.area == CaptureArea::AllMonitors {
+        let mut shots = Vec::new();
+        for monitor in xcap::Monitor::all().map_err(monitor_error)? {
+            shots.push((monitor_bounds(&monitor)?, monitor.capture_image().map_err(capture_error)?));
+        }
+        let (image, bounds) = stitch(shots).ok_or("No monitor found")?;
+        return Ok((image, CaptureGeometry { area: CaptureArea::AllMonitors, bounds, display: None }));
+    }
+
+    let monitor = monitor_for(request.display.as_ref().map(|d| d.id), request.window)?;
+    let display = display_info(&monitor)?;
+    let image = monitor.capture_image().map_err(capture_error)?;
+    let window_crop = match (request.area, request.window) {
+        (CaptureArea::ActiveWindow, Some(window)) => crop(&image, display.bounds, window),
+        _ => None,
+    };
+    Ok(match window_crop {
+        Some((image, bounds)) => {
+            (image, CaptureGeometry { area: CaptureArea::ActiveWindow, bounds, display: Some(display) })
+        }
+        None => {
+            // A window that isn't on its display is captured with the whole display
+            let area = match request.area {
+                CaptureArea::ActiveWindow => CaptureArea::ActiveMonitor,
+                area => area,
+            };
+            (image, CaptureGeometry { area, bounds: display.bounds, display: Some(display) })
+        }
+    })
+}
+
+/// Capture off the async runtime, together with the app that was focused at
+/// that moment
+pub async fn capture_screen(request: ScreenRequest) -> Result<ScreenCapture, String> {
+    tokio::task::spawn_blocking(move || -> Result<ScreenCapture, String> {
+        let (image, geometry) = capture(request)?;
+        let fingerprint = Fingerprint::of_image(&image, geometry.bounds);
+        Ok(ScreenCapture { app_name: super::platform_get_active_app_name(), geometry, fingerprint, image })
+    })
+    .await
+    .map_err(|e| format!("Capture task panicked: {}", e))?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rect(x: f64, y: f64, width: f64, height: f64) -> Rect {
+        Rect { x, y, width, height }
+    }
+
+    #[test]
+    fn crops_window_on_hidpi_monitor() {
+        // Second monitor to the right of the primary, captured at 2x
+        let monitor = rect(1920.0, 0.0, 1440.0, 900.0);
+        let image = RgbaImage::new(2880, 1800);
+        let (cropped, bounds) = crop(&image, monitor, rect(2020.0, 100.0, 400.0, 300.0)).unwrap();
+        assert_eq!(cropped.dimensions(), (800, 600));
+        assert_eq!(bounds, rect(2020.0, 100.0, 400.0, 300.0));
+
+        // Only the part on this monitor is kept
+        let (cropped, bounds) = crop(&image, monitor, rect(1820.0, 0.0, 400.0, 300.0)).unwrap();
+        assert_eq!(cropped.dimensions(), (600, 600));
+        assert_eq!(bounds.x, 1920.0);
+        assert!(crop(&image, monitor, rect(0.0, 0.0, 400.0, 300.0)).is_none());
+    }
+
+    #[test]
+    fn masks_own_windows_at_capture_density() {
+        let white = image::Rgba([255, 255, 255, 255]);
+        let mut image = RgbaImage::from_pixel(2880, 1800, white);
+        let monitor = rect(1920.0, 0.0, 1440.0, 900.0);
+        // Popup near the bottom-right corner, partly off this monitor
+        mask(&mut image, monitor, &[rect(3000.0, 600.0, 400.0, 260.0), rect(0.0, 0.0, 300.0, 300.0)]);
+        assert_eq!(image.get_pixel(2159, 1199).0, [255, 255, 255, 255]);
+        assert_eq!(image.get_pixel(2160, 1200).0, [0, 0, 0, 255]);
+        assert_eq!(image.get_pixel(2879, 1719).0, [0, 0, 0, 255]);
+        assert_eq!(image.get_pixel(2879, 1720).0, [255, 255, 255, 255]);
+        assert_eq!(image.get_pixel(0, 0).0, [255, 255, 255, 255]);
+    }
+
+    #[test]
+    fn stitches_monitors_as_arranged() {
+        let left = RgbaImage::from_pixel(1920, 1080, image::Rgba([255, 0, 0, 255]));
+        let right = RgbaImage::from_pixel(2880, 1800, image::Rgba([0, 0, 255, 255]));
+        let (canvas, bounds) = stitch(vec![
+            (rect(0.0, 0.0, 1920.0, 1080.0), left),
+            (rect(1920.0, -200.0, 1440.0, 900.0), right),
+        ])
+        .unwrap();
+        assert_eq!(bounds, rect(0.0, -200.0, 3360.0, 1280.0));
+        // Drawn at the right monitor's 2x density
+        assert_eq!(canvas.dimensions(), (6720, 2560));
+        assert_eq!(canvas.get_pixel(10, 410).0, [255, 0, 0, 255]);
+        assert_eq!(canvas.get_pixel(3850, 10).0, [0, 0, 255, 255]);
+        assert!(stitch(Vec::new()).is_none());
+    }
+}
@@ -1,6 +1,8 @@
+use crate::accessibility::{self, Node, Role, WindowText};
 use tauri_plugin_global_shortcut::Modifiers;
-use tauri_plugin_store::StoreExt;
-use base64::Engine;
+use uiautomation::controls::ControlType;
+use uiautomation::patterns::{UITextPattern, UIValuePattern};
+use uiautomation::{UIAutomation, UIElement, UITreeWalker};
 
 /// Elevate a window — no-op on Windows (Tauri always_on_top handles it).
 pub fn platform_elevate_window(_window: &tauri::WebviewWindow) {
@@ -46,6 +48,35 @@ pub fn platform_read_clipboard() -> Option<String> {
     if text.is_empty() { None } else { Some(text) }
 }
 
+/// Read the richest clipboard content: HTML, RTF (via clipboard-win), text or image.
+pub fn platform_read_clipboard_content() -> Option<crate::clipboard::ClipboardContent> {
+    crate::clipboard::read_content(|| {
+        let format = clipboard_win::register_format("Rich Text Format")?;
+        let _clip = clipboard_win::Clipboard::new_attempts(10).ok()?;
+        if !clipboard_win::is_format_avail(format.get()) {
+            return None;
+        }
+        let mut data = Vec::new();
+        clipboard_win::raw::get_vec(format.get(), &mut data).ok()?;
+        let rtf = String::from_utf8_lossy(&data).trim_end_matches('\0').to_string();
+        if rtf.is_empty() { None } else { Some(rtf) }
+    })
+}
+
+/// Block forever, calling `on_change` once per clipboard change.
+/// Uses AddClipboardFormatListener (WM_CLIPBOARDUPDATE) on a message-only window.
+pub fn platform_watch_clipboard(on_change: Box<dyn Fn() + Send>) -> Result<(), String> {
+    let mut monitor = clipboard_win::Monitor::new()
+        .map_err(|e| format!("Clipboard listener failed: {}", e))?;
+    loop {
+        match monitor.recv() {
+            Ok(true) => on_change(),
+            Ok(false) => {}
+            Err(e) => return Err(format!("Clipboard listener failed: {}", e)),
+        }
+    }
+}
+
 /// Get the name of the currently active/foreground application.
 pub fn platform_get_active_app_name() -> String {
     match active_win_pos_rs::get_active_window() {
@@ -54,84 +85,133 @@ pub fn platform_get_active_app_name() -> String {
     }
 }
 
-/// Capture the screen and perform OCR via server-side Tesseract.
-/// Takes a screenshot with xcap, compresses it, and sends to the server.
-pub async fn platform_capture_screen_ocr(app_handle: &tauri::AppHandle) -> Result<serde_json::Value, String> {
-    // Steps 1-4 run in spawn_blocking because xcap types are !Send
-    let (base64_image, app_name) = tokio::task::spawn_blocking(|| -> Result<(String, String), String> {
-        // Step 1: Capture screenshot via xcap
-        let monitors = xcap::Monitor::all().map_err(|e| format!("Monitor error: {}", e))?;
-        let primary = monitors
-            .into_iter()
-            .find(|m| m.is_primary().unwrap_or(false))
-            .or_else(|| xcap::Monitor::all().ok()?.into_iter().next())
-            .ok_or("No monitor found")?;
-
-        let image = primary.capture_image().map_err(|e| format!("Capture error: {}", e))?;
-
-        // Step 2: Resize to 25% for bandwidth efficiency
-        let (w, h) = (image.width(), image.height());
-        let resized = image::imageops::resize(
-            &image,
-            w / 4,
-            h / 4,
-            image::imageops::FilterType::Triangle,
-        );
-
-        // Step 3: Encode as JPEG (small size) → base64
-        let mut buf = std::io::Cursor::new(Vec::new());
-        resized
-            .write_to(&mut buf, image::ImageFormat::Jpeg)
-            .map_err(|e| format!("JPEG encode error: {}", e))?;
-        let b64 = base64::engine::general_purpose::STANDARD.encode(buf.into_inner());
-
-        // Step 4: Get active app name
-        let app_name = platform_get_active_app_name();
-
-        Ok((b64, app_name))
-    })
-    .await
-    .map_err(|e| format!("Capture task panicked: {}", e))?
-    ?;
-
-    // Step 5: Get server config
-    let store = app_handle.store("config.json").map_err(|e| e.to_string())?;
-    let url = store
-        .get("api_url")
-        .and_then(|v| v.as_str().map(|s| s.to_string()))
-        .unwrap_or_else(|| "https://reattend.com".to_string());
-    let token = store
-        .get("api_token")
-        .and_then(|v| v.as_str().map(|s| s.to_string()))
-        .unwrap_or_default();
-
-    if token.is_empty() {
-        return Err("No API token configured".to_string());
+/// Get the focused window: title, executable path, PID and bounds.
+pub fn platform_get_active_window() -> Option<super::ActiveWindowInfo> {
+    active_win_pos_rs::get_active_window().ok().map(super::ActiveWindowInfo::from)
+}
+
+/// List connected displays.
+pub async fn platform_list_displays(_app_handle: &tauri::AppHandle) -> Result<Vec<super::DisplayInfo>, String> {
+    super::screenshot::list_displays().await
+}
+
+/// Screenshot the area `request` covers, for OCR.
+pub async fn platform_capture_screen(
+    _app_handle: &tauri::AppHandle,
+    request: &crate::ocr::ScreenRequest,
+) -> Result<crate::ocr::ScreenCapture, String> {
+    super::screenshot::capture_screen(request.clone()).await
+}
+
+/// Tab URLs are not read on Windows, so domain rules give way to title rules
+pub const BROWSER_URLS_READABLE: bool = false;
+
+/// Get the URL of the focused browser tab — not available on Windows without
+/// UI Automation, so attribution falls back to the window title.
+pub async fn platform_get_browser_url(_window: &super::ActiveWindowInfo) -> Option<String> {
+    None
+}
+
+/// Browsers expose no private-mode query on Windows; the window title
+/// ("(Incognito)", "[InPrivate]", "Private Browsing") is the only signal.
+pub async fn platform_is_private_browser_window(_window: &super::ActiveWindowInfo) -> Option<bool> {
+    None
+}
+
+/// UI Automation control type → capture role
+fn uia_role(control_type: ControlType) -> Role {
+    match control_type {
+        ControlType::Document => Role::Document,
+        ControlType::Edit => Role::Editable,
+        ControlType::Text => Role::Text,
+        ControlType::Hyperlink => Role::Link,
+        ControlType::ListItem | ControlType::TreeItem => Role::ListItem,
+        ControlType::DataItem => Role::Cell,
+        ControlType::MenuBar | ControlType::Menu | ControlType::MenuItem | ControlType::ToolBar
+        | ControlType::TitleBar | ControlType::ScrollBar | ControlType::StatusBar | ControlType::Button
+        | ControlType::SplitButton | ControlType::CheckBox | ControlType::RadioButton
+        | ControlType::ComboBox | ControlType::TabItem | ControlType::Slider | ControlType::Spinner
+        | ControlType::Thumb | ControlType::Image | ControlType::ToolTip | ControlType::ProgressBar
+        | ControlType::Separator => Role::Chrome,
+        _ => Role::Container,
+    }
+}
+
+/// Text of an element: the document range for documents and text fields
+/// (capped), the Value pattern otherwise
+fn uia_value(element: &UIElement, role: Role) -> String {
+    const MAX_DOCUMENT_CHARS: i32 = 20_000;
+    if matches!(role, Role::Document | Role::Editable) {
+        if let Ok(text) = element
+            .get_pattern::<UITextPattern>()
+            .and_then(|p| p.get_document_range())
+            .and_then(|r| r.get_text(MAX_DOCUMENT_CHARS))
+        {
+            return text;
+        }
     }
+    element
+        .get_pattern::<UIValuePattern>()
+        .and_then(|p| p.get_value())
+        .unwrap_or_default()
+}
 
-    // Step 6: Send to server for OCR
-    let client = reqwest::Client::new();
-    let resp = client
-        .post(format!("{}/api/tray/ocr", url))
-        .header("Authorization", format!("Bearer {}", token))
-        .json(&serde_json::json!({
-            "image": base64_image,
-            "app_name": &app_name,
-        }))
-        .timeout(std::time::Duration::from_secs(30))
-        .send()
-        .await
-        .map_err(|e| format!("OCR request failed: {}", e))?;
-
-    if !resp.status().is_success() {
-        return Err(format!("OCR API error: {}", resp.status()));
+/// Read the focused window's UI Automation tree. Blocking (COM calls);
+/// call from `spawn_blocking`.
+fn read_window_text() -> Option<WindowText> {
+    let automation = UIAutomation::new().ok()?;
+    let walker = automation.get_control_view_walker().ok()?;
+    let root = automation.get_root_element().ok()?;
+
+    // Walk up from the focused element to its top-level window
+    let focused = automation.get_focused_element().ok()?;
+    let mut window = focused.clone();
+    while let Ok(parent) = walker.get_parent(&window) {
+        if automation.compare_elements(&parent, &root).unwrap_or(true) {
+            break;
+        }
+        window = parent;
     }
 
-    let mut result: serde_json::Value = resp.json().await.map_err(|e| e.to_string())?;
-    if result.get("appName").is_none() {
-        result["appName"] = serde_json::json!(app_name);
+    fn walk(
+        automation: &UIAutomation,
+        walker: &UITreeWalker,
+        focused: &UIElement,
+        element: &UIElement,
+        depth: usize,
+        nodes: &mut Vec<Node>,
+    ) {
+        if nodes.len() >= accessibility::MAX_NODES || depth > accessibility::MAX_DEPTH {
+            return;
+        }
+        let role = element.get_control_type().map(uia_role).unwrap_or(Role::Container);
+        nodes.push(Node {
+            depth,
+            role,
+            name: element.get_name().unwrap_or_default(),
+            value: if role == Role::Chrome { String::new() } else { uia_value(element, role) },
+            focused: automation.compare_elements(element, focused).unwrap_or(false),
+        });
+        // Chrome subtrees are dropped anyway; don't pay for the COM calls
+        if role == Role::Chrome {
+            return;
+        }
+        let mut child = walker.get_first_child(element).ok();
+        while let Some(current) = child {
+            walk(automation, walker, focused, &current, depth + 1, nodes);
+            child = walker.get_next_sibling(&current).ok();
+        }
     }
-    Ok(result)
+
+    let mut nodes = Vec::new();
+    walk(&automation, &walker, &focused, &window, 0, &mut nodes);
+    Some(accessibility::collect(&nodes))
+}
+
+/// Read the focused window's text through UI Automation
+pub async fn platform_get_window_text(_app_handle: &tauri::AppHandle) -> Option<WindowText> {
+    // UIAutomation initialises COM on the calling thread
+    tokio::task::spawn_blocking(read_window_text).await.ok().flatten()
 }
 
 /// Return the platform-appropriate shortcut modifier (Ctrl on Windows).
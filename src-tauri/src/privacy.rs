@@ -0,0 +1,65 @@
+use crate::platform::{self, ActiveWindowInfo, Browser, Platform};
+use tauri_plugin_store::StoreExt;
+
+/// Window-title markers of private browsing windows, per browser.
+/// Matched case-insensitively, and only when the focused app is that browser.
+const PRIVATE_TITLE_MARKERS: &[(Browser, &[&str])] = &[
+    // "New Tab - Google Chrome (Incognito)"
+    (Browser::Chrome, &["(incognito)", "incognito tab"]),
+    // "Mozilla Firefox Private Browsing" / "— Private Browsing"
+    (Browser::Firefox, &["private browsing"]),
+    // "[InPrivate] - Microsoft Edge" / "New InPrivate tab"
+    (Browser::Edge, &["inprivate"]),
+    // "New Private Tab - Brave (Private)" / "Private window with Tor"
+    (Browser::Brave, &["(private)", "private tab", "private window"]),
+    (Browser::Arc, &["incognito", "private window"]),
+];
+
+/// Browsers whose private windows can't be told from normal ones — Safari
+/// exposes no private flag and keeps normal page titles — so every one of
+/// their windows is treated as private
+const UNDETECTABLE_PRIVATE: &[Browser] = &[Browser::Safari];
+
+/// Check without asking the browser: cheap enough to run on every tick.
+pub fn looks_private(window: &ActiveWindowInfo) -> bool {
+    let browser = match platform::browser(&window.app_name) {
+        Some(b) => b,
+        None => return false,
+    };
+    if UNDETECTABLE_PRIVATE.contains(&browser) {
+        return true;
+    }
+    let title = window.title.to_lowercase();
+    PRIVATE_TITLE_MARKERS
+        .iter()
+        .filter(|(b, _)| *b == browser)
+        .flat_map(|(_, markers)| markers.iter())
+        .any(|marker| title.contains(marker))
+}
+
+/// Whether the focused window is a private/incognito browser window.
+/// Uses the window title first, then asks the browser directly where the
+/// platform supports it (AppleScript on macOS for Chromium browsers and Arc).
+pub async fn is_private_window(platform: &dyn Platform, window: &ActiveWindowInfo) -> bool {
+    if looks_private(window) {
+        return true;
+    }
+    if platform::browser(&window.app_name).is_none() {
+        return false;
+    }
+    platform.is_private_browser_window(window).await.unwrap_or(false)
+}
+
+/// Private windows are skipped unless the user opted in (config.json → "capture_private_windows")
+pub fn skip_private_windows(app: &tauri::AppHandle) -> bool {
+    !app.store("config.json")
+        .ok()
+        .and_then(|s| s.get("capture_private_windows"))
+        .and_then(|v| v.as_bool())
+        .unwrap_or(false)
+}
+
+/// Passive capture guard: true when the focused window must not be captured
+pub async fn should_skip_window(app: &tauri::AppHandle, window: &ActiveWindowInfo) -> bool {
+    skip_private_windows(app) && is_private_window(&*platform::get(app), window).await
+}
@@ -0,0 +1,304 @@
+use serde::{Deserialize, Serialize};
+use tauri_plugin_store::StoreExt;
+
+/// User-defined capture rules (config.json → "capture_rules").
+///
+/// Domain patterns: `bank.com` matches the domain and its subdomains,
+/// `*.bank.com` the same, and other `*` wildcards match any run of characters.
+/// Title patterns: case-insensitive substring, or a `*` glob over the whole title.
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(default)]
+pub struct CaptureRules {
+    /// Never capture windows whose title matches
+    pub deny_titles: Vec<String>,
+    /// Never capture browser tabs on these domains
+    pub deny_domains: Vec<String>,
+    /// If any allow rule is set, browser windows are only captured when one matches
+    pub allow_titles: Vec<String>,
+    pub allow_domains: Vec<String>,
+}
+
+impl CaptureRules {
+    fn has_allow_list(&self) -> bool {
+        !self.allow_titles.is_empty() || !self.allow_domains.is_empty()
+    }
+}
+
+pub fn load(app: &tauri::AppHandle) -> CaptureRules {
+    app.store("config.json")
+        .ok()
+        .and_then(|s| s.get("capture_rules"))
+        .and_then(|v| serde_json::from_value(v).ok())
+        .unwrap_or_default()
+}
+
+/// Persist rules, dropping blank patterns
+pub fn save(app: &tauri::AppHandle, mut rules: CaptureRules) -> Result<(), String> {
+    for list in [
+        &mut rules.deny_titles,
+        &mut rules.deny_domains,
+        &mut rules.allow_titles,
+        &mut rules.allow_domains,
+    ] {
+        list.retain(|p| !p.trim().is_empty());
+        for p in list.iter_mut() {
+            *p = p.trim().to_string();
+        }
+    }
+    let store = app.store("config.json").map_err(|e| e.to_string())?;
+    store.set("capture_rules", serde_json::json!(rules));
+    store.save().map_err(|e| e.to_string())
+}
+
+/// Apps whose captures are subject to the domain rules
+fn is_browser(app_name: &str) -> bool {
+    crate::platform::browser(app_name).is_some()
+}
+
+/// Host part of a URL, lowercased, without userinfo or port
+fn url_host(url: &str) -> Option<String> {
+    let rest = url.split_once("://").map(|(_, r)| r).unwrap_or(url);
+    let authority = rest.split(['/', '?', '#']).next()?;
+    let host = authority.rsplit('@').next()?;
+    let host = host.split(':').next()?.trim_end_matches('.');
+    if host.is_empty() { None } else { Some(host.to_lowercase()) }
+}
+
+/// `*` glob over the whole string. Both sides must already be lowercased.
+fn glob_match(pattern: &str, text: &str) -> bool {
+    let p: Vec<char> = pattern.chars().collect();
+    let t: Vec<char> = text.chars().collect();
+    let (mut pi, mut ti) = (0, 0);
+    // Last `*` seen and the text position it is currently matched up to
+    let mut star: Option<(usize, usize)> = None;
+    while ti < t.len() {
+        if pi < p.len() && p[pi] == '*' {
+            star = Some((pi, ti));
+            pi += 1;
+        } else if pi < p.len() && p[pi] == t[ti] {
+            pi += 1;
+            ti += 1;
+        } else if let Some((sp, st)) = star {
+            pi = sp + 1;
+            ti = st + 1;
+            star = Some((sp, st + 1));
+        } else {
+            return false;
+        }
+    }
+    p[pi..].iter().all(|c| *c == '*')
+}
+
+fn domain_matches(pattern: &str, host: &str) -> bool {
+    let pattern = pattern.trim().to_lowercase();
+    let base = pattern.strip_prefix("*.").unwrap_or(&pattern);
+    if base.contains('*') {
+        return glob_match(&pattern, host);
+    }
+    host == base || host.ends_with(&format!(".{}", base))
+}
+
+fn title_matches(pattern: &str, title: &str) -> bool {
+    let pattern = pattern.trim().to_lowercase();
+    let title = title.to_lowercase();
+    if pattern.contains('*') {
+        glob_match(&pattern, &title)
+    } else {
+        title.contains(&pattern)
+    }
+}
+
+/// Check a capture against the rules using its `source_window` metadata
+/// (`title`, `app_name`, `url`). Captures without a source window — manual
+/// selections — are never blocked. Returns the reason when blocked.
+///
+/// When a domain rule or an allow-list is set and a browser tab's URL can't
+/// be read (no Automation permission, Firefox), the capture is blocked: the
+/// rules can't tell whether the page is one they exclude. On platforms that
+/// never read URLs, domain rules are skipped and only title rules apply.
+pub fn check(rules: &CaptureRules, metadata: Option<&serde_json::Value>) -> Result<(), String> {
+    check_on(rules, metadata, crate::platform::BROWSER_URLS_READABLE)
+}
+
+fn check_on(rules: &CaptureRules, metadata: Option<&serde_json::Value>, urls_readable: bool) -> Result<(), String> {
+    let window = match metadata.map(|m| &m["source_window"]) {
+        Some(w) if w.is_object() => w,
+        _ => return Ok(()),
+    };
+    let title = window["title"].as_str().unwrap_or("");
+    let app_name = window["app_name"].as_str().unwrap_or("");
+    let host = window["url"].as_str().and_then(url_host);
+
+    if let Some(p) = rules.deny_titles.iter().find(|p| title_matches(p, title)) {
+        return Err(format!("Blocked by capture rule: title \"{}\"", p));
+    }
+    match host {
+        Some(ref host) => {
+            if let Some(p) = rules.deny_domains.iter().find(|p| domain_matches(p, host)) {
+                return Err(format!("Blocked by capture rule: domain {}", p));
+            }
+        }
+        None if urls_readable && !rules.deny_domains.is_empty() && is_browser(app_name) => {
+            return Err("Blocked by capture rule: page URL unknown".to_string());
+        }
+        None => {}
+    }
+
+    // Without URLs an allow-list can only be judged by its title patterns
+    let has_allow_list = if urls_readable { rules.has_allow_list() } else { !rules.allow_titles.is_empty() };
+    if has_allow_list && is_browser(app_name) {
+        let allowed = rules.allow_titles.iter().any(|p| title_matches(p, title))
+            || host
+                .as_ref()
+                .map(|h| rules.allow_domains.iter().any(|p| domain_matches(p, h)))
+                .unwrap_or(false);
+        if !allowed {
+            return Err("Blocked by capture rule: not on the allow-list".to_string());
+        }
+    }
+    Ok(())
+}
+
+/// How the saved rules behave on this platform, for the settings UI
+#[derive(Debug, Serialize, Clone)]
+pub struct RulesStatus {
+    /// Whether browser tab URLs can be read here at all
+    pub browser_urls_readable: bool,
+    /// Plain-language notes on rules that block more, or less, than they say
+    pub notes: Vec<String>,
+}
+
+pub fn status(rules: &CaptureRules) -> RulesStatus {
+    status_on(rules, crate::platform::BROWSER_URLS_READABLE)
+}
+
+fn status_on(rules: &CaptureRules, urls_readable: bool) -> RulesStatus {
+    let mut notes = Vec::new();
+    let has_domain_rules = !rules.deny_domains.is_empty() || !rules.allow_domains.is_empty();
+    if urls_readable {
+        if has_domain_rules || rules.has_allow_list() {
+            notes.push(
+                "Browser windows whose page URL can't be read (Firefox, or no Automation permission) are not captured"
+                    .to_string(),
+            );
+        }
+    } else {
+        if !rules.deny_domains.is_empty() {
+            notes.push(
+                "Page URLs can't be read on this platform, so domain deny rules are not applied; add title rules instead"
+                    .to_string(),
+            );
+        }
+        if !rules.allow_domains.is_empty() {
+            notes.push(if rules.allow_titles.is_empty() {
+                "Page URLs can't be read on this platform, so the domain allow-list is not applied".to_string()
+            } else {
+                "Page URLs can't be read on this platform, so browser windows are only matched against allowed titles"
+                    .to_string()
+            });
+        }
+    }
+    RulesStatus { browser_urls_readable: urls_readable, notes }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn meta(app_name: &str, title: &str, url: Option<&str>) -> serde_json::Value {
+        let mut window = serde_json::json!({ "app_name": app_name, "title": title });
+        if let Some(url) = url {
+            window["url"] = serde_json::json!(url);
+        }
+        serde_json::json!({ "source_window": window })
+    }
+
+    #[test]
+    fn globs_match_the_whole_text() {
+        assert!(glob_match("*bank*", "my bank account"));
+        assert!(glob_match("inbox - *", "inbox - work"));
+        assert!(glob_match("a*b*c", "aXXbYYc"));
+        assert!(glob_match("*", ""));
+        assert!(!glob_match("inbox - *", "archive - inbox - work"));
+        assert!(!glob_match("a*b*c", "aXXbYY"));
+        assert!(!glob_match("bank", "banking"));
+    }
+
+    #[test]
+    fn domains_match_themselves_and_subdomains() {
+        assert!(domain_matches("bank.com", "bank.com"));
+        assert!(domain_matches("bank.com", "secure.bank.com"));
+        assert!(domain_matches("*.bank.com", "bank.com"));
+        assert!(domain_matches(" Bank.COM ", "online.bank.com"));
+        assert!(domain_matches("mail.*.com", "mail.example.com"));
+        assert!(!domain_matches("bank.com", "mybank.com"));
+        assert!(!domain_matches("bank.com", "bank.com.evil.net"));
+        assert!(!domain_matches("mail.*.com", "mail.example.org"));
+        assert_eq!(url_host("https://user:pw@Secure.Bank.com:8443/login?x=1").as_deref(), Some("secure.bank.com"));
+    }
+
+    #[test]
+    fn deny_rules_block_matching_windows() {
+        let rules = CaptureRules {
+            deny_titles: vec!["*payroll*".to_string()],
+            deny_domains: vec!["bank.com".to_string()],
+            ..Default::default()
+        };
+        assert!(check(&rules, Some(&meta("Google Chrome", "Login", Some("https://www.bank.com/login")))).is_err());
+        assert!(check(&rules, Some(&meta("Excel", "Payroll 2024.xlsx", None))).is_err());
+        assert!(check(&rules, Some(&meta("Google Chrome", "News", Some("https://news.example.com")))).is_ok());
+        assert!(check(&rules, Some(&meta("Notes", "Groceries", None))).is_ok());
+        // Manual selections carry no source window
+        assert!(check(&rules, None).is_ok());
+    }
+
+    #[test]
+    fn domain_rules_fail_closed_without_a_url() {
+        let deny = CaptureRules { deny_domains: vec!["bank.com".to_string()], ..Default::default() };
+        assert!(check_on(&deny, Some(&meta("Firefox", "Online Banking", None)), true).is_err());
+        assert!(check_on(&deny, Some(&meta("Arc", "Online Banking", None)), true).is_err());
+        // Non-browsers have no URL to check
+        assert!(check_on(&deny, Some(&meta("Notes", "Online Banking", None)), true).is_ok());
+
+        let allow = CaptureRules { allow_domains: vec!["docs.google.com".to_string()], ..Default::default() };
+        assert!(check_on(&allow, Some(&meta("Firefox", "Planning - Google Docs", None)), true).is_err());
+        assert!(check_on(&allow, Some(&meta("Firefox", "Planning", Some("https://docs.google.com/d/1"))), true).is_ok());
+        assert!(check_on(&allow, Some(&meta("Notes", "Planning", None)), true).is_ok());
+    }
+
+    #[test]
+    fn title_rules_stand_in_where_urls_are_never_read() {
+        let deny = CaptureRules {
+            deny_titles: vec!["*online banking*".to_string()],
+            deny_domains: vec!["bank.com".to_string()],
+            ..Default::default()
+        };
+        assert!(check_on(&deny, Some(&meta("Google Chrome", "News", None)), false).is_ok());
+        assert!(check_on(&deny, Some(&meta("Google Chrome", "Online Banking", None)), false).is_err());
+
+        let allow_domains = CaptureRules { allow_domains: vec!["docs.google.com".to_string()], ..Default::default() };
+        assert!(check_on(&allow_domains, Some(&meta("Firefox", "Anything", None)), false).is_ok());
+        let allow_titles = CaptureRules {
+            allow_titles: vec!["*google docs*".to_string()],
+            allow_domains: vec!["docs.google.com".to_string()],
+            ..Default::default()
+        };
+        assert!(check_on(&allow_titles, Some(&meta("Firefox", "Planning - Google Docs", None)), false).is_ok());
+        assert!(check_on(&allow_titles, Some(&meta("Firefox", "Inbox", None)), false).is_err());
+    }
+
+    #[test]
+    fn status_explains_rules_that_cannot_apply() {
+        assert!(status_on(&CaptureRules::default(), false).notes.is_empty());
+        let rules = CaptureRules {
+            deny_domains: vec!["bank.com".to_string()],
+            allow_domains: vec!["docs.google.com".to_string()],
+            ..Default::default()
+        };
+        let status = status_on(&rules, false);
+        assert!(!status.browser_urls_readable);
+        assert_eq!(status.notes.len(), 2);
+        assert!(status.notes[1].contains("allow-list is not applied"));
+        assert_eq!(status_on(&rules, true).notes.len(), 1);
+    }
+}
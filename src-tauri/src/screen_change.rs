@@ -0,0 +1,242 @@
+use crate::platform::Rect;
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::sync::{Mutex, OnceLock};
+use tauri_plugin_store::StoreExt;
+
+// Screen OCR is the expensive part of a capture tick (and an upload with
+// server OCR). Each screenshot is first reduced to a small grayscale
+// thumbnail and compared with the thumbnail of the last screenshot that was
+// OCR'd for the same window; OCR only runs when enough of it changed.
+
+/// Thumbnail size: about 15×15 screen pixels per thumbnail pixel on 1080p
+pub const THUMBNAIL_WIDTH: u32 = 128;
+pub const THUMBNAIL_HEIGHT: u32 = 72;
+
+/// Windows whose last OCR'd thumbnail is kept
+const MAX_WINDOWS: usize = 16;
+
+/// Change shares kept for the stats
+const RECENT_CHANGES: usize = 100;
+
+/// Grayscale thumbnail of a screenshot and the area it shows
+#[derive(Debug, Clone, PartialEq)]
+pub struct Fingerprint {
+    pixels: Vec<u8>,
+    bounds: Rect,
+}
+
+impl Fingerprint {
+    /// From `THUMBNAIL_WIDTH` × `THUMBNAIL_HEIGHT` luma bytes, row by row
+    #[cfg(any(test, target_os = "macos"))]
+    pub fn from_luma(pixels: Vec<u8>, bounds: Rect) -> Option<Self> {
+        (pixels.len() == (THUMBNAIL_WIDTH * THUMBNAIL_HEIGHT) as usize).then_some(Self { pixels, bounds })
+    }
+
+    /// From the base64 luma bytes the macOS capture helper returns
+    #[cfg(target_os = "macos")]
+    pub fn from_base64(encoded: &str, bounds: Rect) -> Option<Self> {
+        use base64::Engine;
+        let pixels = base64::engine::general_purpose::STANDARD.decode(encoded).ok()?;
+        Self::from_luma(pixels, bounds)
+    }
+
+    /// Box-filtered thumbnail of a screenshot
+    #[cfg(any(target_os = "windows", target_os = "linux"))]
+    pub fn of_image(image: &image::RgbaImage, bounds: Rect) -> Self {
+        let thumbnail = image::imageops::thumbnail(image, THUMBNAIL_WIDTH, THUMBNAIL_HEIGHT);
+        let luma = image::imageops::grayscale(&thumbnail);
+        Self { pixels: luma.into_raw(), bounds }
+    }
+
+    /// Share of pixels whose brightness moved by at least `threshold`.
+    /// Everything counts as changed when the captured area moved.
+    pub fn changed_share(&self, other: &Fingerprint, threshold: u8) -> f64 {
+        if self.bounds != other.bounds || self.pixels.len() != other.pixels.len() {
+            return 1.0;
+        }
+        let changed = self
+            .pixels
+            .iter()
+            .zip(&other.pixels)
+            .filter(|(a, b)| a.abs_diff(**b) >= threshold)
+            .count();
+        changed as f64 / self.pixels.len() as f64
+    }
+}
+
+/// Change detection settings (config.json → "screen_change")
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ChangeSettings {
+    /// Skip OCR when the window looks the same as at its last OCR
+    pub enabled: bool,
+    /// Brightness difference (0–255) at which a thumbnail pixel counts as changed.
+    /// A blinking text cursor stays below the default.
+    pub pixel_threshold: u8,
+    /// Share of thumbnail pixels (0–1) that must change for OCR to run.
+    /// The default is about one typed word on a 1080p screen.
+    pub min_changed: f64,
+    /// Run OCR anyway after this many skips in a row
+    pub max_skips: u32,
+}
+
+impl Default for ChangeSettings {
+    fn default() -> Self {
+        Self { enabled: true, pixel_threshold: 24, min_changed: 0.001, max_skips: 10 }
+    }
+}
+
+pub fn load_settings(app: &tauri::AppHandle) -> ChangeSettings {
+    app.store("config.json")
+        .ok()
+        .and_then(|s| s.get("screen_change"))
+        .and_then(|v| serde_json::from_value(v).ok())
+        .unwrap_or_default()
+}
+
+pub fn save_settings(app: &tauri::AppHandle, settings: ChangeSettings) -> Result<(), String> {
+    if !(0.0..=1.0).contains(&settings.min_changed) {
+        return Err("min_changed must be between 0 and 1".to_string());
+    }
+    let store = app.store("config.json").map_err(|e| e.to_string())?;
+    store.set("screen_change", serde_json::json!(settings));
+    store.save().map_err(|e| e.to_string())
+}
+
+/// Counters for tuning the thresholds
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ChangeStats {
+    /// Screenshots compared
+    pub checked: u64,
+    /// OCR runs skipped because too little changed
+    pub skipped: u64,
+    /// OCR runs forced by `max_skips`
+    pub forced: u64,
+    /// Changed share of the most recent comparisons, oldest first
+    pub recent_changes: VecDeque<f64>,
+}
+
+/// Last OCR'd thumbnail per window, and the stats
+#[derive(Default)]
+pub struct ChangeDetector {
+    /// Most recently used first
+    windows: VecDeque<(String, Fingerprint)>,
+    skips_in_a_row: u32,
+    stats: ChangeStats,
+}
+
+impl ChangeDetector {
+    /// Whether OCR should run on a screenshot of window `key`. When it
+    /// should, the screenshot becomes the one later ones are compared with.
+    pub fn should_ocr(&mut self, key: &str, fingerprint: Fingerprint, settings: &ChangeSettings) -> bool {
+        let previous = self.windows.iter().position(|(k, _)| k == key).and_then(|i| self.windows.remove(i));
+        let Some((_, previous)) = previous else {
+            self.remember(key, fingerprint);
+            return true;
+        };
+
+        let change = fingerprint.changed_share(&previous, settings.pixel_threshold);
+        self.stats.checked += 1;
+        if self.stats.recent_changes.len() == RECENT_CHANGES {
+            self.stats.recent_changes.pop_front();
+        }
+        self.stats.recent_changes.push_back(change);
+
+        if change < settings.min_changed {
+            if self.skips_in_a_row < settings.max_skips {
+                self.skips_in_a_row += 1;
+                self.stats.skipped += 1;
+                self.windows.push_front((key.to_string(), previous));
+                return false;
+            }
+            self.stats.forced += 1;
+        }
+        self.skips_in_a_row = 0;
+        self.remember(key, fingerprint);
+        true
+    }
+
+    fn remember(&mut self, key: &str, fingerprint: Fingerprint) {
+        self.windows.push_front((key.to_string(), fingerprint));
+        self.windows.truncate(MAX_WINDOWS);
+    }
+
+    pub fn stats(&self) -> ChangeStats {
+        self.stats.clone()
+    }
+}
+
+fn detector() -> std::sync::MutexGuard<'static, ChangeDetector> {
+    static DETECTOR: OnceLock<Mutex<ChangeDetector>> = OnceLock::new();
+    DETECTOR
+        .get_or_init(Default::default)
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+}
+
+/// Check a screenshot against the app-wide detector. Always true when
+/// change detection is off.
+pub fn should_ocr(app: &tauri::AppHandle, key: &str, fingerprint: Fingerprint) -> bool {
+    let settings = load_settings(app);
+    !settings.enabled || detector().should_ocr(key, fingerprint, &settings)
+}
+
+pub fn stats() -> ChangeStats {
+    detector().stats()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SCREEN: Rect = Rect { x: 0.0, y: 0.0, width: 1920.0, height: 1080.0 };
+
+    /// Thumbnail with the first `changed` pixels brightened by `delta`
+    fn thumbnail(changed: usize, delta: u8) -> Fingerprint {
+        let mut pixels = vec![100u8; (THUMBNAIL_WIDTH * THUMBNAIL_HEIGHT) as usize];
+        for pixel in pixels.iter_mut().take(changed) {
+            *pixel += delta;
+        }
+        Fingerprint::from_luma(pixels, SCREEN).unwrap()
+    }
+
+    #[test]
+    fn counts_pixels_past_the_threshold() {
+        let base = thumbnail(0, 0);
+        assert_eq!(base.changed_share(&thumbnail(0, 0), 24), 0.0);
+        // A blinking cursor barely moves a thumbnail pixel
+        assert_eq!(base.changed_share(&thumbnail(2, 15), 24), 0.0);
+        let share = base.changed_share(&thumbnail(92, 60), 24);
+        assert!((share - 92.0 / 9216.0).abs() < 1e-9);
+
+        // A moved or resized window is a different picture
+        let moved = Fingerprint { bounds: Rect { x: 10.0, ..SCREEN }, ..thumbnail(0, 0) };
+        assert_eq!(base.changed_share(&moved, 24), 1.0);
+        assert!(Fingerprint::from_luma(vec![0; 10], SCREEN).is_none());
+    }
+
+    #[test]
+    fn skips_unchanged_windows_until_forced() {
+        let settings = ChangeSettings { max_skips: 2, ..Default::default() };
+        let mut detector = ChangeDetector::default();
+        assert!(detector.should_ocr("editor", thumbnail(0, 0), &settings));
+        assert!(!detector.should_ocr("editor", thumbnail(3, 60), &settings));
+        assert!(!detector.should_ocr("editor", thumbnail(5, 60), &settings));
+        // Third skip in a row: OCR anyway
+        assert!(detector.should_ocr("editor", thumbnail(5, 60), &settings));
+
+        // Changes add up against the last OCR'd screenshot, so slow typing
+        // still gets read
+        assert!(!detector.should_ocr("editor", thumbnail(8, 60), &settings));
+        assert!(detector.should_ocr("editor", thumbnail(15, 60), &settings));
+
+        // Each window is compared with its own last screenshot
+        assert!(detector.should_ocr("browser", thumbnail(0, 0), &settings));
+        assert!(!detector.should_ocr("editor", thumbnail(20, 60), &settings));
+
+        let stats = detector.stats();
+        assert_eq!((stats.checked, stats.skipped, stats.forced), (6, 4, 1));
+        assert_eq!(stats.recent_changes.len(), 6);
+    }
+}
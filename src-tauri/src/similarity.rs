@@ -0,0 +1,286 @@
+use serde::{Deserialize, Serialize};
+
+/// Bins per MinHash signature; the estimate's standard error is about
+/// 1/√SIGNATURE_LEN (≈0.09)
+pub const SIGNATURE_LEN: usize = 128;
+
+/// Characters per shingle. Short enough that one misread character only
+/// changes a few shingles, long enough to keep neighbouring words in order.
+const SHINGLE_CHARS: usize = 5;
+
+/// Default for config.json → "screen_similarity_threshold": screen text at
+/// least this similar to the previous OCR counts as unchanged
+pub const DEFAULT_THRESHOLD: f64 = 0.75;
+
+/// splitmix64 finalizer — cheap, well-distributed 64-bit mixing
+fn mix(mut x: u64) -> u64 {
+    x ^= x >> 30;
+    x = x.wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    x ^= x >> 27;
+    x = x.wrapping_mul(0x94d0_49bb_1331_11eb);
+    x ^ (x >> 31)
+}
+
+/// One in `SKETCH_RATE` shingle hashes is kept in a containment sketch
+const SKETCH_RATE: u64 = 4;
+
+/// Most hashes kept per sketch; about 2000 characters of text at `SKETCH_RATE`
+const SKETCH_LEN: usize = 512;
+
+/// Fewest sampled shingles a text needs before its containment is trusted
+const MIN_SKETCH_SAMPLES: usize = 8;
+
+const FNV_OFFSET: u64 = 0xcbf2_9ce4_8422_2325;
+const FNV_PRIME: u64 = 0x0100_0000_01b3;
+
+/// Lowercase, drop punctuation, collapse whitespace, and fold characters
+/// OCR commonly confuses (0/o, 1/l/I/|), so re-reads of the same screen agree.
+pub fn normalize(text: &str) -> String {
+    text.split_whitespace()
+        .map(|word| {
+            word.chars()
+                .filter_map(|c| match c {
+                    '|' => Some('l'),
+                    c if c.is_alphanumeric() => c.to_lowercase().next(),
+                    _ => None,
+                })
+                .map(|c| match c {
+                    '0' => 'o',
+                    '1' | 'i' => 'l',
+                    _ => c,
+                })
+                .collect::<String>()
+        })
+        .filter(|w| !w.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Hashes of the character shingles of normalized text: FNV-1a followed by
+/// `mix`, computed without allocating per shingle. Stable across builds,
+/// unlike `DefaultHasher`, so they can be persisted.
+pub fn shingles(text: &str) -> Vec<u64> {
+    let chars: Vec<char> = normalize(text).chars().collect();
+    if chars.is_empty() {
+        return Vec::new();
+    }
+    let mut utf8 = [0u8; 4];
+    chars
+        .windows(SHINGLE_CHARS.min(chars.len()))
+        .map(|w| {
+            let mut hash = FNV_OFFSET;
+            for c in w {
+                for b in c.encode_utf8(&mut utf8).bytes() {
+                    hash ^= b as u64;
+                    hash = hash.wrapping_mul(FNV_PRIME);
+                }
+            }
+            mix(hash)
+        })
+        .collect()
+}
+
+/// One-permutation MinHash signature: each shingle hash lands in one of
+/// `SIGNATURE_LEN` bins (top bits) and each bin keeps its minimum. Building it
+/// is a single pass over the text; comparing two is O(SIGNATURE_LEN), so the
+/// previous screen only needs hashing once.
+#[derive(Debug, Clone)]
+pub struct MinHash {
+    mins: Vec<u64>,
+}
+
+impl MinHash {
+    pub fn of(text: &str) -> Self {
+        let mut mins = vec![u64::MAX; SIGNATURE_LEN];
+        for h in shingles(text) {
+            let bin = (h >> 57) as usize % SIGNATURE_LEN;
+            let value = h & ((1 << 57) - 1);
+            if value < mins[bin] {
+                mins[bin] = value;
+            }
+        }
+        Self { mins }
+    }
+
+    /// Estimated Jaccard similarity in 0..=1. Bins empty in both texts carry
+    /// no information and are skipped. Two empty texts are identical.
+    pub fn similarity(&self, other: &MinHash) -> f64 {
+        let mut used = 0;
+        let mut equal = 0;
+        for (a, b) in self.mins.iter().zip(&other.mins) {
+            if *a == u64::MAX && *b == u64::MAX {
+                continue;
+            }
+            used += 1;
+            if a == b {
+                equal += 1;
+            }
+        }
+        if used == 0 { 1.0 } else { equal as f64 / used as f64 }
+    }
+}
+
+/// Shingle hashes sampled by value rather than by position, so a passage
+/// keeps the same samples wherever it appears: a paragraph quoted inside a
+/// longer text shares nearly all of its samples with it, however far apart
+/// the two texts' MinHash or SimHash are. Long texts keep only their lowest
+/// `SKETCH_LEN` samples, which stays a consistent sample of the hash range.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Sketch {
+    hashes: Vec<u64>,
+    /// Whether samples above the last kept one were dropped
+    truncated: bool,
+}
+
+impl Sketch {
+    pub fn of(text: &str) -> Self {
+        let mut hashes: Vec<u64> = shingles(text).into_iter().filter(|h| h % SKETCH_RATE == 0).collect();
+        hashes.sort_unstable();
+        hashes.dedup();
+        let truncated = hashes.len() > SKETCH_LEN;
+        hashes.truncate(SKETCH_LEN);
+        Self { hashes, truncated }
+    }
+
+    /// Highest hash this sketch has complete samples up to
+    fn limit(&self) -> u64 {
+        if self.truncated { self.hashes.last().copied().unwrap_or(0) } else { u64::MAX }
+    }
+
+    /// Estimated share of this text's shingles that also occur in `other`, in
+    /// 0..=1. None when the text is too short to tell.
+    pub fn contained_in(&self, other: &Sketch) -> Option<f64> {
+        let limit = self.limit().min(other.limit());
+        let own: Vec<u64> = self.hashes.iter().copied().take_while(|h| *h <= limit).collect();
+        if own.len() < MIN_SKETCH_SAMPLES {
+            return None;
+        }
+        let shared = own.iter().filter(|h| other.hashes.binary_search(h).is_ok()).count();
+        Some(shared as f64 / own.len() as f64)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn similarity(a: &str, b: &str) -> f64 {
+        MinHash::of(a).similarity(&MinHash::of(b))
+    }
+
+    /// The word-set Jaccard this module replaced, kept as the baseline
+    fn word_jaccard(a: &str, b: &str) -> f64 {
+        let words_a: std::collections::HashSet<&str> = a.split_whitespace().collect();
+        let words_b: std::collections::HashSet<&str> = b.split_whitespace().collect();
+        if words_a.is_empty() && words_b.is_empty() {
+            return 1.0;
+        }
+        let intersection = words_a.intersection(&words_b).count();
+        let union = words_a.union(&words_b).count();
+        intersection as f64 / union as f64
+    }
+
+    /// Recorded OCR snapshot pairs; `same_*` show unchanged content, `changed_*` new content
+    const SAMPLES: &[(&str, bool, &str)] = &[
+        ("same_rescan", true, include_str!("../tests/fixtures/ocr/same_rescan.txt")),
+        ("same_reflow", true, include_str!("../tests/fixtures/ocr/same_reflow.txt")),
+        ("same_cursor", true, include_str!("../tests/fixtures/ocr/same_cursor.txt")),
+        ("changed_page", false, include_str!("../tests/fixtures/ocr/changed_page.txt")),
+        ("changed_thread", false, include_str!("../tests/fixtures/ocr/changed_thread.txt")),
+    ];
+
+    fn pair(raw: &str) -> (&str, &str) {
+        raw.split_once("\n=====\n").expect("sample needs a ===== separator")
+    }
+
+    #[test]
+    fn classifies_recorded_samples() {
+        for (name, same, raw) in SAMPLES {
+            let (a, b) = pair(raw);
+            let score = similarity(a, b);
+            assert_eq!(score >= DEFAULT_THRESHOLD, *same, "{}: {:.2}", name, score);
+        }
+    }
+
+    #[test]
+    fn ocr_noise_scores_higher_than_with_word_jaccard() {
+        let (a, b) = pair(SAMPLES[0].2);
+        assert!(similarity(a, b) > word_jaccard(a, b) + 0.1);
+        for (name, same, raw) in SAMPLES {
+            if *same {
+                let (a, b) = pair(raw);
+                assert!(similarity(a, b) >= word_jaccard(a, b), "{}", name);
+            }
+        }
+    }
+
+    #[test]
+    fn normalize_folds_case_punctuation_and_ocr_confusions() {
+        assert_eq!(normalize("Hello,  W0rld!\n|ine 1"), "hello world llne l");
+    }
+
+    #[test]
+    fn sketch_finds_a_paragraph_inside_a_longer_text() {
+        let (a, b) = pair(SAMPLES[3].2);
+        let paragraph = a.lines().find(|l| l.len() > 60).expect("sample has a long line");
+        let whole = Sketch::of(a);
+        assert!(Sketch::of(paragraph).contained_in(&whole).unwrap() > 0.9);
+        // Containment is one-way: the page holds far more than the paragraph
+        assert!(whole.contained_in(&Sketch::of(paragraph)).unwrap() < 0.5);
+        assert!(Sketch::of(b).contained_in(&whole).unwrap() < 0.5);
+        assert_eq!(Sketch::of("short").contained_in(&whole), None);
+
+        // Truncated sketches still compare over the range both cover
+        let long: String = (0..1500u32)
+            .map(|i| {
+                let n = i.wrapping_mul(2_654_435_761) >> 8;
+                (0..5).map(|k| (b'a' + ((n >> (k * 4)) & 15) as u8) as char).collect::<String>() + " "
+            })
+            .collect();
+        let sketch = Sketch::of(&long);
+        assert!(sketch.truncated);
+        assert!(Sketch::of(&long[..3000]).contained_in(&sketch).unwrap() > 0.9);
+    }
+
+    #[test]
+    fn empty_texts() {
+        assert_eq!(similarity("", ""), 1.0);
+        assert_eq!(similarity("", "some text"), 0.0);
+    }
+
+    /// `cargo test --release -- --ignored --nocapture bench_against_word_jaccard`
+    #[test]
+    #[ignore]
+    fn bench_against_word_jaccard() {
+        const ROUNDS: u32 = 200;
+        let pairs: Vec<(&str, &str)> = SAMPLES.iter().map(|(_, _, raw)| pair(raw)).collect();
+
+        let start = std::time::Instant::now();
+        for _ in 0..ROUNDS {
+            for (a, b) in &pairs {
+                std::hint::black_box(word_jaccard(a, b));
+            }
+        }
+        let jaccard = start.elapsed();
+
+        // The capture loop keeps the previous signature, so each cycle hashes one text
+        let previous: Vec<MinHash> = pairs.iter().map(|(a, _)| MinHash::of(a)).collect();
+        let start = std::time::Instant::now();
+        for _ in 0..ROUNDS {
+            for ((_, b), prev) in pairs.iter().zip(&previous) {
+                std::hint::black_box(MinHash::of(b).similarity(prev));
+            }
+        }
+        let minhash = start.elapsed();
+
+        println!("{:<16} {:>8} {:>8}", "sample", "jaccard", "minhash");
+        for ((name, _, _), (a, b)) in SAMPLES.iter().zip(&pairs) {
+            println!("{:<16} {:>8.2} {:>8.2}", name, word_jaccard(a, b), similarity(a, b));
+        }
+        println!(
+            "per comparison: jaccard {:?}, minhash {:?}",
+            jaccard / (ROUNDS * pairs.len() as u32),
+            minhash / (ROUNDS * pairs.len() as u32),
+        );
+    }
+}
@@ -0,0 +1,83 @@
+use tauri::{menu::MenuItem, Manager};
+use tauri_plugin_notification::NotificationExt;
+use tauri_plugin_store::StoreExt;
+
+/// How long after a manual save the "Undo Last Save" tray item stays active
+pub const UNDO_WINDOW_SECS: u64 = 30;
+
+/// Tray menu item for undo — held in Tauri state so it can be enabled/disabled
+pub struct UndoMenuItem(pub MenuItem<tauri::Wry>);
+
+/// The most recent manual save that can still be undone
+struct PendingUndo {
+    server_id: String,
+    generation: u64,
+}
+
+static PENDING: std::sync::Mutex<Option<PendingUndo>> = std::sync::Mutex::new(None);
+static GENERATION: std::sync::atomic::AtomicU64 = std::sync::atomic::AtomicU64::new(0);
+
+fn set_menu_enabled(app: &tauri::AppHandle, enabled: bool) {
+    if let Some(item) = app.try_state::<UndoMenuItem>() {
+        let _ = item.0.set_enabled(enabled);
+    }
+}
+
+/// Offer undo for a capture that was just saved. Replaces any earlier offer.
+/// Returns false when there is nothing to undo (the server returned no id).
+pub fn offer(app: &tauri::AppHandle, server_id: &str) -> bool {
+    if server_id.is_empty() {
+        return false;
+    }
+    let generation = GENERATION.fetch_add(1, std::sync::atomic::Ordering::SeqCst) + 1;
+    *PENDING.lock().unwrap_or_else(|e| e.into_inner()) = Some(PendingUndo {
+        server_id: server_id.to_string(),
+        generation,
+    });
+    set_menu_enabled(app, true);
+
+    // Expire the offer once the window closes, unless a newer save replaced it
+    let handle = app.clone();
+    tauri::async_runtime::spawn(async move {
+        tokio::time::sleep(tokio::time::Duration::from_secs(UNDO_WINDOW_SECS)).await;
+        let mut pending = PENDING.lock().unwrap_or_else(|e| e.into_inner());
+        if pending.as_ref().map(|p| p.generation) == Some(generation) {
+            *pending = None;
+            set_menu_enabled(&handle, false);
+        }
+    });
+    true
+}
+
+/// Retract the most recent manual save, if still inside the undo window
+pub async fn undo_last(app_handle: tauri::AppHandle) {
+    let pending = PENDING.lock().unwrap_or_else(|e| e.into_inner()).take();
+    set_menu_enabled(&app_handle, false);
+    let server_id = match pending {
+        Some(p) => p.server_id,
+        None => return,
+    };
+
+    let store = match app_handle.store("config.json") {
+        Ok(s) => s,
+        Err(_) => return,
+    };
+    let token = store
+        .get("api_token")
+        .and_then(|v| v.as_str().map(|s| s.to_string()))
+        .unwrap_or_default();
+    let url = store
+        .get("api_url")
+        .and_then(|v| v.as_str().map(|s| s.to_string()))
+        .unwrap_or_else(|| "https://reattend.com".to_string());
+
+    let body = match crate::capture::retract(&app_handle, &url, &token, &server_id).await {
+        Ok(()) => "Removed the last saved capture.".to_string(),
+        Err(e) => format!("Failed to undo: {}", e),
+    };
+    let _ = app_handle.notification()
+        .builder()
+        .title("Reattend")
+        .body(&body)
+        .show();
+}
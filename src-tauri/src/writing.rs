@@ -0,0 +1,235 @@
+/// A session ends after this long without new text written in it
+pub const IDLE_SECS: u64 = 90;
+
+/// Sessions with fewer words than this in their final text are not uploaded
+pub const MIN_SESSION_WORDS: usize = 15;
+
+/// Window title with "unsaved changes" markers removed, so that editing a
+/// document doesn't look like switching to another one
+pub fn document_key(title: &str) -> String {
+    let mut key = title.trim();
+    for prefix in ["•", "●", "*"] {
+        key = key.strip_prefix(prefix).unwrap_or(key).trim_start();
+    }
+    for suffix in ["— Edited", "- Edited", "*", "•", "●"] {
+        key = key.strip_suffix(suffix).unwrap_or(key).trim_end();
+    }
+    // Unread counters: "(3) Inbox - Gmail"
+    if key.starts_with('(') {
+        if let Some((count, rest)) = key[1..].split_once(')') {
+            if count.chars().all(|c| c.is_ascii_digit()) {
+                key = rest.trim_start();
+            }
+        }
+    }
+    key.to_lowercase()
+}
+
+fn now_millis() -> i64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis() as i64
+}
+
+/// Writing in progress in one document
+struct Session {
+    app_name: String,
+    document: String,
+    started_at: i64,
+    last_write: std::time::Instant,
+    last_write_at: i64,
+    /// Inserted runs, one per diff hunk
+    fragments: Vec<String>,
+    /// Some of the writing happened while the view scrolled
+    scrolled: bool,
+    source_window: Option<serde_json::Value>,
+}
+
+/// A finished session, ready to upload as one `writing` capture
+#[derive(Debug, Clone)]
+pub struct FinishedSession {
+    pub app_name: String,
+    pub text: String,
+    pub started_at: i64,
+    pub ended_at: i64,
+    pub fragments: usize,
+    pub scrolled: bool,
+    pub source_window: Option<serde_json::Value>,
+}
+
+impl FinishedSession {
+    pub fn duration_secs(&self) -> i64 {
+        (self.ended_at - self.started_at).max(0) / 1000
+    }
+}
+
+/// Groups writing deltas from successive OCR snapshots into sessions keyed by
+/// app and document (window title). A session ends when the user goes idle,
+/// switches app, or moves to another document.
+#[derive(Default)]
+pub struct WritingTracker {
+    current: Option<Session>,
+}
+
+/// Whether `words` occur in `run` one after another
+fn contains_words(run: &[&str], words: &[&str]) -> bool {
+    !words.is_empty() && run.windows(words.len()).any(|w| w == words)
+}
+
+impl WritingTracker {
+    pub const fn new() -> Self {
+        Self { current: None }
+    }
+
+    /// Record newly written text. Returns the previous session if this write
+    /// belongs to a different app or document.
+    pub fn add(
+        &mut self,
+        app_name: &str,
+        title: &str,
+        delta: &crate::diff::WritingDelta,
+        source_window: Option<serde_json::Value>,
+    ) -> Option<FinishedSession> {
+        let document = document_key(title);
+        let finished = match self.current {
+            Some(ref s) if s.app_name == app_name && s.document == document => None,
+            Some(_) => self.finish(),
+            None => None,
+        };
+        let session = self.current.get_or_insert_with(|| Session {
+            app_name: app_name.to_string(),
+            document,
+            started_at: now_millis(),
+            last_write: std::time::Instant::now(),
+            last_write_at: 0,
+            fragments: Vec::new(),
+            scrolled: false,
+            source_window: None,
+        });
+        session.fragments.extend(delta.text.lines().map(str::to_string));
+        session.scrolled |= delta.scroll.is_some();
+        session.last_write = std::time::Instant::now();
+        session.last_write_at = now_millis();
+        if source_window.is_some() {
+            session.source_window = source_window;
+        }
+        finished
+    }
+
+    /// Drop fragments of the current session that the user deleted again,
+    /// so the consolidated capture holds the final text rather than every
+    /// draft. `removed` holds the deleted runs, one per line; a fragment goes
+    /// when its exact words were deleted, alone or as part of a longer run.
+    pub fn remove(&mut self, app_name: &str, title: &str, removed: &str) {
+        let s = match self.current {
+            Some(ref mut s) if s.app_name == app_name && s.document == document_key(title) => s,
+            _ => return,
+        };
+        let runs: Vec<Vec<&str>> = removed.lines().map(|run| run.split_whitespace().collect()).collect();
+        s.fragments.retain(|fragment| {
+            let words: Vec<&str> = fragment.split_whitespace().collect();
+            !runs.iter().any(|run| contains_words(run, &words))
+        });
+    }
+
+    /// Called with the focused window on every check. Ends the session on an
+    /// app switch, a document change, or after `IDLE_SECS` without writing.
+    pub fn observe(&mut self, app_name: &str, title: &str) -> Option<FinishedSession> {
+        self.observe_at(app_name, title, std::time::Instant::now())
+    }
+
+    fn observe_at(&mut self, app_name: &str, title: &str, now: std::time::Instant) -> Option<FinishedSession> {
+        let s = self.current.as_ref()?;
+        let idle = now.duration_since(s.last_write) >= std::time::Duration::from_secs(IDLE_SECS);
+        if idle || s.app_name != app_name || s.document != document_key(title) {
+            self.finish()
+        } else {
+            None
+        }
+    }
+
+    /// End the current session. Returns None if too little text was written.
+    pub fn finish(&mut self) -> Option<FinishedSession> {
+        let s = self.current.take()?;
+        let text = s.fragments.join("\n");
+        if text.split_whitespace().count() < MIN_SESSION_WORDS {
+            return None;
+        }
+        Some(FinishedSession {
+            app_name: s.app_name,
+            text,
+            started_at: s.started_at,
+            ended_at: s.last_write_at,
+            fragments: s.fragments.len(),
+            scrolled: s.scrolled,
+            source_window: s.source_window,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::diff::WritingDelta;
+    use std::time::{Duration, Instant};
+
+    const FIRST: &str = "The migration plan moves every workspace to the new storage cluster";
+    const SECOND: &str = "before the end of the quarter, starting with the smallest teams first";
+
+    fn delta(text: &str) -> WritingDelta {
+        WritingDelta { text: text.to_string(), inserted_words: text.split_whitespace().count(), ..Default::default() }
+    }
+
+    fn writing(app_name: &str, title: &str) -> WritingTracker {
+        let mut tracker = WritingTracker::new();
+        assert!(tracker.add(app_name, title, &delta(FIRST), None).is_none());
+        assert!(tracker.add(app_name, title, &delta(SECOND), None).is_none());
+        tracker
+    }
+
+    #[test]
+    fn session_ends_after_idle_timeout() {
+        let mut tracker = writing("Pages", "Plan.pages");
+        let now = Instant::now();
+        assert!(tracker.observe_at("Pages", "Plan.pages", now).is_none());
+        let session = tracker
+            .observe_at("Pages", "Plan.pages", now + Duration::from_secs(IDLE_SECS + 1))
+            .expect("idle session is finished");
+        assert_eq!(session.text, format!("{}\n{}", FIRST, SECOND));
+        assert_eq!(session.fragments, 2);
+        assert!(tracker.finish().is_none());
+    }
+
+    #[test]
+    fn session_ends_on_app_switch() {
+        let mut tracker = writing("Pages", "Plan.pages");
+        let session = tracker.observe("Safari", "Plan.pages").expect("app switch ends the session");
+        assert_eq!(session.app_name, "Pages");
+    }
+
+    #[test]
+    fn session_ends_on_document_change() {
+        let mut tracker = writing("Pages", "Plan.pages");
+        // Unsaved-changes markers are the same document
+        assert!(tracker.observe("Pages", "● Plan.pages — Edited").is_none());
+        let finished = tracker.add("Pages", "Budget.pages", &delta("Travel costs"), None);
+        assert_eq!(finished.expect("new document ends the session").fragments, 2);
+        // The new session is too short to upload
+        assert!(tracker.finish().is_none());
+    }
+
+    #[test]
+    fn remove_drops_exactly_the_deleted_fragments() {
+        let mut tracker = writing("Pages", "Plan.pages");
+        tracker.add("Pages", "Plan.pages", &delta("storage cluster notes to revisit"), None);
+        // Shares most words with FIRST, but FIRST itself is still there
+        tracker.remove("Pages", "Plan.pages", "the new storage cluster every workspace");
+        // Deleted together with text written before the session
+        tracker.remove("Pages", "Plan.pages", "Draft:\nOld intro storage cluster notes to revisit and more");
+        // Another document's deletions don't count
+        tracker.remove("Pages", "Budget.pages", SECOND);
+        let session = tracker.finish().unwrap();
+        assert_eq!(session.text, format!("{}\n{}", FIRST, SECOND));
+    }
+}
@@ -6,29 +6,136 @@ import Foundation
 // MARK: - Screen Capture + OCR CLI Tool
 // Usage:
 //   reattend-capture screenshot   → captures screen, runs OCR, prints JSON
+//   reattend-capture ocr-image <path> → runs OCR on an image file, prints JSON
 //   reattend-capture active-app   → prints the name of the active application
+//   reattend-capture ax-text      → prints the focused window's accessibility tree as JSON
+//   reattend-capture serve        → long-lived JSON-RPC server on stdin/stdout (see below)
 
 struct CaptureResult: Codable {
     let text: String
     let appName: String
     let timestamp: String
     let confidence: Double
+    /// Screen area that was read (screenshots only)
+    var bounds: Region? = nil
 }
 
 // MARK: - Screenshot
 
-func captureScreen() -> CGImage? {
-    // Capture the main display
-    let displayID = CGMainDisplayID()
+/// Rectangle in points, in global screen coordinates (origin at the main
+/// display's top-left corner, y down)
+struct Region: Codable {
+    let x: Double
+    let y: Double
+    let width: Double
+    let height: Double
+}
+
+extension Region {
+    init(_ rect: CGRect) {
+        self.init(x: rect.minX, y: rect.minY, width: rect.width, height: rect.height)
+    }
+
+    var cgRect: CGRect {
+        CGRect(x: x, y: y, width: width, height: height)
+    }
+}
+
+struct Capture {
+    let image: CGImage
+    /// Captured area in global coordinates
+    let bounds: CGRect
+}
+
+/// Display holding the center of `rect`, else the main display
+func displayContaining(_ rect: CGRect) -> CGDirectDisplayID {
+    var display: CGDirectDisplayID = 0
+    var count: UInt32 = 0
+    let center = CGPoint(x: rect.midX, y: rect.midY)
+    if CGGetDisplaysWithPoint(center, 1, &display, &count) == .success, count > 0 {
+        return display
+    }
+    return CGMainDisplayID()
+}
+
+/// Capture `region` cropped to `display` (by default the display holding
+/// it), or a whole display (the main one by default)
+func captureScreen(display: CGDirectDisplayID? = nil, region: CGRect? = nil) -> Capture? {
+    let displayID = display ?? region.map(displayContaining) ?? CGMainDisplayID()
+    let displayBounds = CGDisplayBounds(displayID)
+    if let region = region {
+        let crop = region.intersection(displayBounds)
+        guard !crop.isNull, !crop.isEmpty else {
+            return nil
+        }
+        let local = crop.offsetBy(dx: -displayBounds.minX, dy: -displayBounds.minY)
+        guard let image = CGDisplayCreateImage(displayID, rect: local) else {
+            return nil
+        }
+        return Capture(image: image, bounds: crop)
+    }
     guard let image = CGDisplayCreateImage(displayID) else {
         return nil
     }
-    return image
+    return Capture(image: image, bounds: displayBounds)
+}
+
+func activeDisplays() -> [CGDirectDisplayID] {
+    var count: UInt32 = 0
+    guard CGGetActiveDisplayList(0, nil, &count) == .success, count > 0 else {
+        return []
+    }
+    var displays = [CGDirectDisplayID](repeating: 0, count: Int(count))
+    guard CGGetActiveDisplayList(count, &displays, &count) == .success else {
+        return []
+    }
+    return Array(displays.prefix(Int(count)))
+}
+
+func captureAllDisplays() -> [Capture] {
+    return activeDisplays().compactMap { captureScreen(display: $0) }
+}
+
+// MARK: - Displays
+
+struct DisplayInfo: Codable {
+    let id: UInt32
+    let name: String
+    let bounds: Region
+    let scaleFactor: Double
+    let primary: Bool
+}
+
+func listDisplays() -> [DisplayInfo] {
+    return activeDisplays().map { id in
+        let screen = NSScreen.screens.first {
+            ($0.deviceDescription[NSDeviceDescriptionKey("NSScreenNumber")] as? NSNumber)?.uint32Value == id
+        }
+        var name = "Display \(id)"
+        if #available(macOS 10.15, *), let screen = screen {
+            name = screen.localizedName
+        }
+        return DisplayInfo(
+            id: id,
+            name: name,
+            bounds: Region(CGDisplayBounds(id)),
+            scaleFactor: Double(screen?.backingScaleFactor ?? 1),
+            primary: CGDisplayIsMain(id) != 0
+        )
+    }
+}
+
+func loadImage(path: String) -> CGImage? {
+    guard let nsImage = NSImage(contentsOfFile: path) else {
+        return nil
+    }
+    var rect = NSRect(origin: .zero, size: nsImage.size)
+    return nsImage.cgImage(forProposedRect: &rect, context: nil, hints: nil)
 }
 
 // MARK: - OCR via Vision
 
-func recognizeText(from image: CGImage) -> (String, Double) {
+func recognizeText(from image: CGImage, languages: [String] = []) -> (String, Double) {
     let semaphore = DispatchSemaphore(value: 0)
     var resultText = ""
     var avgConfidence: Double = 0
@@ -58,6 +165,9 @@ func recognizeText(from image: CGImage) -> (String, Double) {
 
     request.recognitionLevel = .accurate
     request.usesLanguageCorrection = true
+    if !languages.isEmpty {
+        request.recognitionLanguages = languages
+    }
 
     let handler = VNImageRequestHandler(cgImage: image, options: [:])
     do {
@@ -80,6 +190,60 @@ func getActiveAppName() -> String {
     return "Unknown"
 }
 
+// MARK: - Accessibility Tree
+
+struct AXNode: Codable {
+    let depth: Int
+    let role: String
+    let name: String
+    let value: String
+    let focused: Bool
+}
+
+func axAttribute(_ element: AXUIElement, _ attribute: String) -> AnyObject? {
+    var value: AnyObject?
+    guard AXUIElementCopyAttributeValue(element, attribute as CFString, &value) == .success else {
+        return nil
+    }
+    return value
+}
+
+/// Flatten the focused window's accessibility tree in document order,
+/// stopping early once `timeLimit` seconds have passed.
+/// Returns nil without Accessibility permission or a focused window.
+func collectAXNodes(maxNodes: Int, maxDepth: Int, timeLimit: TimeInterval = .infinity) -> [AXNode]? {
+    guard AXIsProcessTrusted(), let app = NSWorkspace.shared.frontmostApplication else {
+        return nil
+    }
+    let axApp = AXUIElementCreateApplication(app.processIdentifier)
+    // Unresponsive apps must not stall the capture loop
+    AXUIElementSetMessagingTimeout(axApp, 0.5)
+    guard let window = axAttribute(axApp, kAXFocusedWindowAttribute) else {
+        return nil
+    }
+    let focused = axAttribute(axApp, kAXFocusedUIElementAttribute)
+
+    let deadline = Date().addingTimeInterval(timeLimit)
+    var nodes: [AXNode] = []
+    func walk(_ element: AXUIElement, depth: Int) {
+        if nodes.count >= maxNodes || depth > maxDepth || Date() > deadline { return }
+        let role = axAttribute(element, kAXRoleAttribute) as? String ?? ""
+        let title = axAttribute(element, kAXTitleAttribute) as? String ?? ""
+        let name = title.isEmpty ? (axAttribute(element, kAXDescriptionAttribute) as? String ?? "") : title
+        let value = axAttribute(element, kAXValueAttribute) as? String ?? ""
+        let isFocused = focused.map { CFEqual($0, element) } ?? false
+        nodes.append(AXNode(depth: depth, role: role, name: name, value: value, focused: isFocused))
+
+        if let children = axAttribute(element, kAXChildrenAttribute) as? [AXUIElement] {
+            for child in children {
+                walk(child, depth: depth + 1)
+            }
+        }
+    }
+    walk(window as! AXUIElement, depth: 0)
+    return nodes
+}
+
 // MARK: - Clipboard
 
 func getClipboardText() -> String? {
@@ -112,12 +276,315 @@ func activateApp(pid: Int32) {
     }
 }
 
+// MARK: - JSON-RPC Server
+// `reattend-capture serve` reads one JSON-RPC 2.0 request per line on stdin
+// and writes one response per line on stdout, until stdin closes:
+//   hello                                   → {version, protocol, capabilities}
+//   screenshot {display?, region?, allDisplays?, languages?, exclude?} → CaptureResult
+//   ocr_image {path, languages?}            → CaptureResult
+//   ax_text {maxNodes?, maxDepth?, timeLimitMs?} → [AXNode]
+//   active_app                              → {name}
+//   displays                                → [DisplayInfo]
+//   capture {display?, region?, allDisplays?, exclude?} → {id, bounds, appName, fingerprint}
+//   ocr_capture {id, languages?}            → CaptureResult
+//
+// `capture` keeps the pixels for a later `ocr_capture`, so the caller can
+// skip OCR when the fingerprint (a base64 128×72 grayscale thumbnail, rows
+// top to bottom) shows nothing changed. Only the latest capture is kept.
+
+let helperVersion = "1.5.0"
+let protocolVersion = 1
+let capabilities = ["hello", "screenshot", "ocr_image", "ax_text", "active_app", "displays", "capture", "ocr_capture"]
+
+let fingerprintWidth = 128
+let fingerprintHeight = 72
+
+struct HelloResult: Codable {
+    let version: String
+    let `protocol`: Int
+    let capabilities: [String]
+}
+
+struct ScreenshotParams: Codable {
+    let display: UInt32?
+    let region: Region?
+    /// OCR every display; the text is joined in display order
+    let allDisplays: Bool?
+    let languages: [String]?
+    /// Areas (our own windows) to black out of the screenshot
+    let exclude: [Region]?
+}
+
+struct CaptureResponse: Codable {
+    let id: UInt64
+    let bounds: Region
+    let appName: String
+    let fingerprint: String
+}
+
+struct OCRCaptureParams: Codable {
+    let id: UInt64
+    let languages: [String]?
+}
+
+/// Captures from the last `capture` call, waiting for `ocr_capture`
+var storedCaptures: (id: UInt64, captures: [Capture])? = nil
+var nextCaptureID: UInt64 = 1
+
+struct OCRImageParams: Codable {
+    let path: String
+    let languages: [String]?
+}
+
+struct AXTextParams: Codable {
+    let maxNodes: Int?
+    let maxDepth: Int?
+    let timeLimitMs: Int?
+}
+
+struct ActiveAppResult: Codable {
+    let name: String
+}
+
+struct RPCError: Error {
+    let code: Int
+    let message: String
+}
+
+func decodeParams<T: Decodable>(_ params: Any?, as type: T.Type) throws -> T {
+    guard let data = try? JSONSerialization.data(withJSONObject: params ?? [String: Any]()),
+          let decoded = try? JSONDecoder().decode(type, from: data) else {
+        throw RPCError(code: -32602, message: "Invalid params")
+    }
+    return decoded
+}
+
+func encodeResult<T: Encodable>(_ value: T) throws -> Data {
+    guard let data = try? JSONEncoder().encode(value) else {
+        throw RPCError(code: -32603, message: "Failed to encode result")
+    }
+    return data
+}
+
+func ocrResult(for image: CGImage, languages: [String]?) -> CaptureResult {
+    let (text, confidence) = recognizeText(from: image, languages: languages ?? [])
+    return CaptureResult(
+        text: text,
+        appName: getActiveAppName(),
+        timestamp: ISO8601DateFormatter().string(from: Date()),
+        confidence: confidence
+    )
+}
+
+/// Capture what a `screenshot` or `capture` request asks for
+func captures(for p: ScreenshotParams) throws -> [Capture] {
+    let captures: [Capture]
+    if p.allDisplays == true {
+        captures = captureAllDisplays()
+    } else {
+        captures = captureScreen(display: p.display, region: p.region?.cgRect).map { [$0] } ?? []
+    }
+    guard !captures.isEmpty else {
+        throw RPCError(code: 1, message: "Failed to capture screen (is Screen Recording permission granted?)")
+    }
+    let exclude = (p.exclude ?? []).map { $0.cgRect }
+    return captures.map { mask($0, excluding: exclude) }
+}
+
+/// `capture` with the parts of `rects` (global coordinates) inside it blacked out
+func mask(_ capture: Capture, excluding rects: [CGRect]) -> Capture {
+    let overlaps = rects.map { $0.intersection(capture.bounds) }.filter { !$0.isNull && !$0.isEmpty }
+    guard !overlaps.isEmpty else {
+        return capture
+    }
+    let image = capture.image
+    guard let context = CGContext(
+        data: nil,
+        width: image.width,
+        height: image.height,
+        bitsPerComponent: 8,
+        bytesPerRow: 0,
+        space: CGColorSpaceCreateDeviceRGB(),
+        bitmapInfo: CGImageAlphaInfo.premultipliedLast.rawValue
+    ) else {
+        return capture
+    }
+    context.draw(image, in: CGRect(x: 0, y: 0, width: image.width, height: image.height))
+    context.setFillColor(CGColor(gray: 0, alpha: 1))
+    let scaleX = CGFloat(image.width) / capture.bounds.width
+    let scaleY = CGFloat(image.height) / capture.bounds.height
+    for rect in overlaps {
+        // Bitmap contexts have y up; global coordinates have y down
+        context.fill(CGRect(
+            x: (rect.minX - capture.bounds.minX) * scaleX,
+            y: (capture.bounds.maxY - rect.maxY) * scaleY,
+            width: rect.width * scaleX,
+            height: rect.height * scaleY
+        ))
+    }
+    guard let masked = context.makeImage() else {
+        return capture
+    }
+    return Capture(image: masked, bounds: capture.bounds)
+}
+
+func captureBounds(_ captures: [Capture]) -> CGRect {
+    return captures.dropFirst().reduce(captures[0].bounds) { $0.union($1.bounds) }
+}
+
+/// OCR each capture; the text is joined in display order
+func ocrResult(for captures: [Capture], languages: [String]?) -> CaptureResult {
+    var texts: [String] = []
+    var confidences: [Double] = []
+    for capture in captures {
+        let (text, confidence) = recognizeText(from: capture.image, languages: languages ?? [])
+        if !text.isEmpty {
+            texts.append(text)
+            confidences.append(confidence)
+        }
+    }
+    return CaptureResult(
+        text: texts.joined(separator: "\n\n"),
+        appName: getActiveAppName(),
+        timestamp: ISO8601DateFormatter().string(from: Date()),
+        confidence: confidences.isEmpty ? 0 : confidences.reduce(0, +) / Double(confidences.count),
+        bounds: Region(captureBounds(captures))
+    )
+}
+
+/// Grayscale thumbnail of the captures laid out within `bounds`, base64
+func fingerprint(of captures: [Capture], in bounds: CGRect) -> String {
+    guard let context = CGContext(
+        data: nil,
+        width: fingerprintWidth,
+        height: fingerprintHeight,
+        bitsPerComponent: 8,
+        bytesPerRow: fingerprintWidth,
+        space: CGColorSpaceCreateDeviceGray(),
+        bitmapInfo: CGImageAlphaInfo.none.rawValue
+    ) else {
+        return ""
+    }
+    context.interpolationQuality = .low
+    let scaleX = CGFloat(fingerprintWidth) / bounds.width
+    let scaleY = CGFloat(fingerprintHeight) / bounds.height
+    for capture in captures {
+        // Bitmap contexts have y up; global coordinates have y down
+        let rect = CGRect(
+            x: (capture.bounds.minX - bounds.minX) * scaleX,
+            y: (bounds.maxY - capture.bounds.maxY) * scaleY,
+            width: capture.bounds.width * scaleX,
+            height: capture.bounds.height * scaleY
+        )
+        context.draw(capture.image, in: rect)
+    }
+    guard let data = context.data else {
+        return ""
+    }
+    // Row 0 of the bitmap memory is the top row
+    return Data(bytes: data, count: fingerprintWidth * fingerprintHeight).base64EncodedString()
+}
+
+func handleRequest(method: String, params: Any?) throws -> Data {
+    switch method {
+    case "hello":
+        return try encodeResult(HelloResult(version: helperVersion, protocol: protocolVersion, capabilities: capabilities))
+
+    case "screenshot":
+        let p = try decodeParams(params, as: ScreenshotParams.self)
+        return try encodeResult(ocrResult(for: try captures(for: p), languages: p.languages))
+
+    case "capture":
+        let p = try decodeParams(params, as: ScreenshotParams.self)
+        let shots = try captures(for: p)
+        let id = nextCaptureID
+        nextCaptureID += 1
+        storedCaptures = (id, shots)
+        let bounds = captureBounds(shots)
+        return try encodeResult(CaptureResponse(
+            id: id,
+            bounds: Region(bounds),
+            appName: getActiveAppName(),
+            fingerprint: fingerprint(of: shots, in: bounds)
+        ))
+
+    case "ocr_capture":
+        let p = try decodeParams(params, as: OCRCaptureParams.self)
+        guard let stored = storedCaptures, stored.id == p.id else {
+            throw RPCError(code: 1, message: "Capture \(p.id) is no longer available")
+        }
+        storedCaptures = nil
+        return try encodeResult(ocrResult(for: stored.captures, languages: p.languages))
+
+    case "ocr_image":
+        let p = try decodeParams(params, as: OCRImageParams.self)
+        guard let image = loadImage(path: p.path) else {
+            throw RPCError(code: 1, message: "Failed to load image at \(p.path)")
+        }
+        return try encodeResult(ocrResult(for: image, languages: p.languages))
+
+    case "ax_text":
+        let p = try decodeParams(params, as: AXTextParams.self)
+        let timeLimit = p.timeLimitMs.map { TimeInterval($0) / 1000 } ?? .infinity
+        guard let nodes = collectAXNodes(maxNodes: p.maxNodes ?? 2000, maxDepth: p.maxDepth ?? 50, timeLimit: timeLimit) else {
+            throw RPCError(code: 1, message: "No accessible focused window (is Accessibility permission granted?)")
+        }
+        return try encodeResult(nodes)
+
+    case "active_app":
+        return try encodeResult(ActiveAppResult(name: getActiveAppName()))
+
+    case "displays":
+        return try encodeResult(listDisplays())
+
+    default:
+        throw RPCError(code: -32601, message: "Method not found: \(method)")
+    }
+}
+
+func writeResponse(id: Any, result: Data? = nil, error: RPCError? = nil) {
+    var response: [String: Any] = ["jsonrpc": "2.0", "id": id]
+    if let error = error {
+        response["error"] = ["code": error.code, "message": error.message]
+    } else {
+        response["result"] = result.flatMap { try? JSONSerialization.jsonObject(with: $0, options: .fragmentsAllowed) } ?? NSNull()
+    }
+    // Single line: the client reads one response per line
+    guard let data = try? JSONSerialization.data(withJSONObject: response),
+          let line = String(data: data, encoding: .utf8) else {
+        return
+    }
+    print(line)
+    fflush(stdout)
+}
+
+func serve() {
+    while let line = readLine() {
+        if line.trimmingCharacters(in: .whitespaces).isEmpty { continue }
+        guard let data = line.data(using: .utf8),
+              let request = try? JSONSerialization.jsonObject(with: data) as? [String: Any],
+              let method = request["method"] as? String else {
+            writeResponse(id: NSNull(), error: RPCError(code: -32700, message: "Parse error"))
+            continue
+        }
+        let id = request["id"] ?? NSNull()
+        do {
+            let result = try autoreleasepool { try handleRequest(method: method, params: request["params"]) }
+            writeResponse(id: id, result: result)
+        } catch let error as RPCError {
+            writeResponse(id: id, error: error)
+        } catch {
+            writeResponse(id: id, error: RPCError(code: -32603, message: "\(error)"))
+        }
+    }
+}
+
 // MARK: - Main
 
 let args = CommandLine.arguments
 
 if args.count < 2 {
-    fputs("Usage: reattend-capture [screenshot|active-app|clipboard|activate <pid>]\n", stderr)
+    fputs("Usage: reattend-capture [screenshot|ocr-image <path>|active-app|ax-text [max-nodes] [max-depth]|clipboard|activate <pid>|serve]\n", stderr)
     exit(1)
 }
 
@@ -125,7 +592,7 @@ let command = args[1]
 
 switch command {
 case "screenshot":
-    guard let image = captureScreen() else {
+    guard let image = captureScreen()?.image else {
         fputs("Failed to capture screen\n", stderr)
         exit(1)
     }
@@ -147,10 +614,43 @@ case "screenshot":
         print(json)
     }
 
+case "ocr-image":
+    guard args.count >= 3, let image = loadImage(path: args[2]) else {
+        fputs("Usage: reattend-capture ocr-image <path>\n", stderr)
+        exit(1)
+    }
+
+    let (text, confidence) = recognizeText(from: image)
+    let result = CaptureResult(
+        text: text,
+        appName: getActiveAppName(),
+        timestamp: ISO8601DateFormatter().string(from: Date()),
+        confidence: confidence
+    )
+
+    let encoder = JSONEncoder()
+    if let data = try? encoder.encode(result),
+       let json = String(data: data, encoding: .utf8) {
+        print(json)
+    }
+
 case "active-app":
     let name = getActiveAppName()
     print(name)
 
+case "ax-text":
+    let maxNodes = args.count >= 3 ? Int(args[2]) ?? 2000 : 2000
+    let maxDepth = args.count >= 4 ? Int(args[3]) ?? 50 : 50
+    guard let nodes = collectAXNodes(maxNodes: maxNodes, maxDepth: maxDepth) else {
+        fputs("No accessible focused window (is Accessibility permission granted?)\n", stderr)
+        exit(1)
+    }
+    let encoder = JSONEncoder()
+    if let data = try? encoder.encode(nodes),
+       let json = String(data: data, encoding: .utf8) {
+        print(json)
+    }
+
 case "clipboard":
     if let text = getClipboardText() {
         print(text)
@@ -165,6 +665,9 @@ case "activate":
         exit(1)
     }
 
+case "serve":
+    serve()
+
 default:
     fputs("Unknown command: \(command)\n", stderr)
     exit(1)